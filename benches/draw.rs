@@ -127,6 +127,45 @@ fn parser_benchmarks(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("draw indexed 4BPP to RGB565", |b| {
+        let mut fb = Framebuffer::<Rgb565>::new();
+        b.iter(|| {
+            let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/logo-indexed-4bpp.bmp"))
+                .unwrap();
+            Image::new(&bmp, Point::zero()).draw(&mut fb).unwrap();
+        })
+    });
+
+    c.bench_function("draw indexed 8BPP to RGB565", |b| {
+        let mut fb = Framebuffer::<Rgb565>::new();
+        b.iter(|| {
+            let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/logo-indexed-8bpp.bmp"))
+                .unwrap();
+            Image::new(&bmp, Point::zero()).draw(&mut fb).unwrap();
+        })
+    });
+
+    // Baselines for the indexed benchmarks, which look up the color of each pixel in the color
+    // table instead of using a precomputed lookup table. Run the benchmarks with
+    // `--no-default-features` to compare 8 bpp images without the `large-lut` feature.
+    c.bench_function("draw indexed 4BPP per pixel", |b| {
+        let mut fb = Framebuffer::<Rgb888>::new();
+        b.iter(|| {
+            let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/logo-indexed-4bpp.bmp"))
+                .unwrap();
+            fb.draw_iter(bmp.pixels()).unwrap();
+        })
+    });
+
+    c.bench_function("draw indexed 8BPP per pixel", |b| {
+        let mut fb = Framebuffer::<Rgb888>::new();
+        b.iter(|| {
+            let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/logo-indexed-8bpp.bmp"))
+                .unwrap();
+            fb.draw_iter(bmp.pixels()).unwrap();
+        })
+    });
+
     c.bench_function("draw dynamic RGB565 to RGB888", |b| {
         let mut fb = Framebuffer::<Rgb888>::new();
         b.iter(|| {
@@ -44,7 +44,7 @@ fn display_bmp<C>(data: &[u8], settings: &OutputSettings)
 where
     C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888> + Into<Rgb888>,
 {
-    let bmp = Bmp::<C>::from_slice(&data).unwrap();
+    let bmp = Bmp::<C>::from_slice(data).unwrap();
 
     let mut display = SimulatorDisplay::<Rgb888>::new(bmp.size());
 
@@ -52,7 +52,7 @@ where
         .draw(&mut display.color_converted())
         .unwrap();
 
-    let mut window = Window::new("BMP viewer", &settings);
+    let mut window = Window::new("BMP viewer", settings);
     window.show_static(&display);
 }
 
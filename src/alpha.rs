@@ -0,0 +1,163 @@
+use embedded_graphics::{
+    image::GetPixel,
+    pixelcolor::{raw::RawU24, Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{raw_bmp::ColorType, spans::fill_area, Bmp};
+
+/// Alpha channel handling mode.
+///
+/// See [`Bmp::with_alpha`] for more information.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum AlphaMode {
+    /// Skips pixels with an alpha value below the threshold.
+    ///
+    /// All other pixels are drawn fully opaque. A threshold of `1` only skips fully transparent
+    /// pixels.
+    Threshold(u8),
+
+    /// Blends all pixels with a fixed background color.
+    Background(Rgb888),
+}
+
+/// BMP image which is drawn with transparency.
+///
+/// This struct is created by [`Bmp::with_alpha`] and can be drawn to any [`DrawTarget`] by
+/// wrapping it in an [`Image`](embedded_graphics::image::Image). See the [`AlphaMode`] docs for the
+/// supported ways to handle transparent pixels. Images without an alpha channel are drawn like
+/// the wrapped [`Bmp`].
+///
+/// Blending with the existing content of a [`DrawTarget`] isn't supported, because draw targets
+/// don't provide read access to their content.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct AlphaBmp<'a, C> {
+    bmp: Bmp<'a, C>,
+    mode: AlphaMode,
+}
+
+impl<'a, C> AlphaBmp<'a, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    pub(crate) const fn new(bmp: Bmp<'a, C>, mode: AlphaMode) -> Self {
+        Self { bmp, mode }
+    }
+
+    /// Returns a reference to the wrapped BMP image.
+    pub const fn as_bmp(&self) -> &Bmp<'a, C> {
+        &self.bmp
+    }
+
+    /// Returns the alpha mode.
+    pub const fn mode(&self) -> AlphaMode {
+        self.mode
+    }
+
+    /// Converts a raw pixel value into a color.
+    ///
+    /// Returns `None` if the pixel isn't drawn.
+    fn color(&self, raw: u32) -> Option<C> {
+        let color_type = self.bmp.as_raw().color_type;
+
+        let color = match color_type {
+            ColorType::Bitfields16(bitfields) | ColorType::Bitfields32(bitfields) => {
+                bitfields.to_rgb888(raw)
+            }
+            _ => Rgb888::from(RawU24::new(raw)),
+        };
+        let alpha = color_type.alpha(raw);
+
+        match self.mode {
+            AlphaMode::Threshold(threshold) => (alpha >= threshold).then(|| color.into()),
+            AlphaMode::Background(background) => Some(blend(color, background, alpha).into()),
+        }
+    }
+}
+
+impl<C> ImageDrawable for AlphaBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let raw_bmp = self.bmp.as_raw();
+        if !raw_bmp.has_alpha() {
+            return self.bmp.draw(target);
+        }
+
+        fill_area(raw_bmp, target, &self.bounding_box(), |_, raw| {
+            self.color(raw)
+        })
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let raw_bmp = self.bmp.as_raw();
+        if !raw_bmp.has_alpha() {
+            return self.bmp.draw_sub_image(target, area);
+        }
+
+        // Only the part of the area which overlaps the image is decoded.
+        let source = area.intersection(&self.bounding_box());
+        if source.is_zero_sized() {
+            return Ok(());
+        }
+
+        fill_area(
+            raw_bmp,
+            &mut target.translated(source.top_left - area.top_left),
+            &source,
+            |_, raw| self.color(raw),
+        )
+    }
+}
+
+impl<C> OriginDimensions for AlphaBmp<'_, C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.bmp.size()
+    }
+}
+
+impl<C> GetPixel for AlphaBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    /// Returns the color of a pixel.
+    ///
+    /// Returns `None` if `p` is outside the image bounding box or if the pixel is skipped by the
+    /// alpha mode.
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        if self.bmp.as_raw().has_alpha() {
+            self.color(self.bmp.as_raw().pixel(p)?)
+        } else {
+            self.bmp.pixel(p)
+        }
+    }
+}
+
+/// Blends a color with a background color.
+fn blend(color: Rgb888, background: Rgb888, alpha: u8) -> Rgb888 {
+    let alpha = u16::from(alpha);
+    let channel = |foreground: u8, background: u8| {
+        ((u16::from(foreground) * alpha + u16::from(background) * (255 - alpha) + 127) / 255) as u8
+    };
+
+    Rgb888::new(
+        channel(color.r(), background.r()),
+        channel(color.g(), background.g()),
+        channel(color.b(), background.b()),
+    )
+}
@@ -0,0 +1,247 @@
+//! Conversion of `BI_BITFIELDS` pixel values.
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::{ChannelMasks, ParseError};
+
+/// Position and width of a single color channel inside a raw pixel value.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub(crate) struct Channel {
+    shift: u8,
+    bits: u8,
+}
+
+impl Channel {
+    /// Creates a channel from a bit mask.
+    ///
+    /// Returns `None` if the set bits in the mask aren't contiguous.
+    const fn new(mask: u32) -> Option<Self> {
+        if mask == 0 {
+            return Some(Self { shift: 0, bits: 0 });
+        }
+
+        let shift = mask.trailing_zeros();
+        let bits = (mask >> shift).trailing_ones();
+
+        match (mask >> shift).checked_shr(bits) {
+            Some(0) | None => Some(Self {
+                shift: shift as u8,
+                bits: bits as u8,
+            }),
+            Some(_) => None,
+        }
+    }
+
+    /// Returns the maximum value of the channel.
+    const fn max(self) -> u32 {
+        if self.bits == 0 {
+            0
+        } else {
+            u32::MAX >> (32 - self.bits as u32)
+        }
+    }
+
+    /// Extracts the channel from a raw pixel value and scales it to 8 bits.
+    const fn extract(self, raw: u32) -> u8 {
+        let max = self.max();
+        if max == 0 {
+            return 0;
+        }
+
+        let value = (raw >> self.shift) & max;
+
+        if self.bits >= 8 {
+            (value >> (self.bits - 8)) as u8
+        } else {
+            ((value * 255 + max / 2) / max) as u8
+        }
+    }
+
+    /// Scales an 8 bit value to the channel width and moves it into position.
+    const fn insert(self, value: u8) -> u32 {
+        let max = self.max() as u64;
+        let scaled = (value as u64 * max + 127) / 255;
+
+        (scaled as u32) << self.shift
+    }
+}
+
+/// Precomputed channel positions for images which use arbitrary channel masks.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub(crate) struct Bitfields {
+    red: Channel,
+    green: Channel,
+    blue: Channel,
+    alpha: Channel,
+}
+
+impl Bitfields {
+    /// Creates a new bitfields converter.
+    ///
+    /// Returns an error if a mask isn't contiguous or doesn't fit into `bits` bits.
+    pub(crate) const fn new(masks: &ChannelMasks, bits: u16) -> Result<Self, ParseError> {
+        let valid_bits = if bits >= 32 {
+            u32::MAX
+        } else {
+            (1 << bits) - 1
+        };
+
+        if (masks.red | masks.green | masks.blue | masks.alpha) & !valid_bits != 0 {
+            return Err(ParseError::UnsupportedChannelMasks);
+        }
+
+        match (
+            Channel::new(masks.red),
+            Channel::new(masks.green),
+            Channel::new(masks.blue),
+            Channel::new(masks.alpha),
+        ) {
+            (Some(red), Some(green), Some(blue), Some(alpha)) => Ok(Self {
+                red,
+                green,
+                blue,
+                alpha,
+            }),
+            _ => Err(ParseError::UnsupportedChannelMasks),
+        }
+    }
+
+    /// Converts a raw pixel value into a `Rgb888` color.
+    pub(crate) const fn to_rgb888(self, raw: u32) -> Rgb888 {
+        Rgb888::new(
+            self.red.extract(raw),
+            self.green.extract(raw),
+            self.blue.extract(raw),
+        )
+    }
+
+    /// Converts a `Rgb888` color into a raw pixel value.
+    ///
+    /// The alpha channel, if present, is set to fully opaque.
+    pub(crate) fn raw_from_rgb888(self, color: Rgb888) -> u32 {
+        self.red.insert(color.r())
+            | self.green.insert(color.g())
+            | self.blue.insert(color.b())
+            | self.alpha.insert(255)
+    }
+
+    /// Returns `true` if the masks include an alpha channel.
+    pub(crate) const fn has_alpha(self) -> bool {
+        self.alpha.bits > 0
+    }
+
+    /// Extracts the alpha value from a raw pixel value.
+    ///
+    /// Returns `255` (opaque) if the masks don't include an alpha channel.
+    pub(crate) const fn alpha(self, raw: u32) -> u8 {
+        if self.has_alpha() {
+            self.alpha.extract(raw)
+        } else {
+            255
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_masks() {
+        let rgb565 = Bitfields::new(&ChannelMasks::RGB565, 16).unwrap();
+        assert_eq!(rgb565.to_rgb888(0xF800), Rgb888::new(255, 0, 0));
+        assert_eq!(rgb565.to_rgb888(0x07E0), Rgb888::new(0, 255, 0));
+        assert_eq!(rgb565.to_rgb888(0x001F), Rgb888::new(0, 0, 255));
+        assert_eq!(rgb565.to_rgb888(0x8410), Rgb888::new(132, 130, 132));
+
+        let rgb888 = Bitfields::new(&ChannelMasks::RGB888, 32).unwrap();
+        assert_eq!(rgb888.to_rgb888(0xFF123456), Rgb888::new(0x12, 0x34, 0x56));
+        assert_eq!(rgb888.alpha(0x00123456), 255);
+    }
+
+    #[test]
+    fn alpha() {
+        let argb1555 = ChannelMasks {
+            alpha: 0x8000,
+            ..ChannelMasks::RGB555
+        };
+        let bitfields = Bitfields::new(&argb1555, 16).unwrap();
+        assert!(bitfields.has_alpha());
+        assert_eq!(bitfields.alpha(0x8000), 255);
+        assert_eq!(bitfields.alpha(0x7FFF), 0);
+        assert_eq!(bitfields.to_rgb888(0xFC00), Rgb888::new(255, 0, 0));
+
+        let argb8888 = Bitfields::new(&ChannelMasks::ARGB8888, 32).unwrap();
+        assert_eq!(argb8888.alpha(0x80FFFFFF), 0x80);
+    }
+
+    #[test]
+    fn wide_channels() {
+        // R10G10B10
+        let masks = ChannelMasks {
+            red: 0x3FF0_0000,
+            green: 0x000F_FC00,
+            blue: 0x0000_03FF,
+            alpha: 0,
+        };
+        let bitfields = Bitfields::new(&masks, 32).unwrap();
+
+        assert_eq!(
+            bitfields.to_rgb888(0x3FF0_0000 | 0x0008_0000 | 0x0000_0004),
+            Rgb888::new(255, 128, 1)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let masks = [
+            (ChannelMasks::RGB555, 16),
+            (ChannelMasks::RGB565, 16),
+            (ChannelMasks::ARGB8888, 32),
+            (
+                ChannelMasks {
+                    red: 0x3FF0_0000,
+                    green: 0x000F_FC00,
+                    blue: 0x0000_03FF,
+                    alpha: 0xC000_0000,
+                },
+                32,
+            ),
+        ];
+
+        for (masks, bits) in masks {
+            let bitfields = Bitfields::new(&masks, bits).unwrap();
+            for value in [0, 1, 127, 128, 200, 255] {
+                let color = Rgb888::new(value, 255 - value, value / 2);
+                let raw = bitfields.raw_from_rgb888(color);
+                assert_eq!(bitfields.alpha(raw), 255);
+                assert_eq!(
+                    bitfields.raw_from_rgb888(bitfields.to_rgb888(raw)),
+                    raw,
+                    "{masks:?}"
+                );
+            }
+        }
+
+        let rgb565 = Bitfields::new(&ChannelMasks::RGB565, 16).unwrap();
+        assert_eq!(rgb565.raw_from_rgb888(Rgb888::new(255, 0, 0)), 0xF800);
+        assert_eq!(rgb565.raw_from_rgb888(Rgb888::WHITE), 0xFFFF);
+    }
+
+    #[test]
+    fn invalid_masks() {
+        let non_contiguous = ChannelMasks {
+            red: 0b1010_0000_0000_0000,
+            ..ChannelMasks::RGB555
+        };
+        assert_eq!(
+            Bitfields::new(&non_contiguous, 16),
+            Err(ParseError::UnsupportedChannelMasks)
+        );
+
+        assert_eq!(
+            Bitfields::new(&ChannelMasks::RGB888, 16),
+            Err(ParseError::UnsupportedChannelMasks)
+        );
+    }
+}
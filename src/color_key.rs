@@ -0,0 +1,144 @@
+use embedded_graphics::{
+    image::GetPixel,
+    pixelcolor::{Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{lookup_table::LookupTable, spans::fill_area, Bmp};
+
+/// Color key which marks transparent pixels.
+///
+/// See [`Bmp::with_transparent_color`] and [`Bmp::with_transparent_index`] for more information.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ColorKey<C> {
+    /// Pixels with this color are transparent.
+    ///
+    /// The color is compared after the pixels are converted into the target color type.
+    Color(C),
+
+    /// Pixels with this color index are transparent.
+    ///
+    /// Images without a color table don't contain transparent pixels.
+    Index(u8),
+}
+
+/// BMP image which is drawn with a transparent color key.
+///
+/// This struct is created by [`Bmp::with_transparent_color`] or [`Bmp::with_transparent_index`]
+/// and can be drawn to any [`DrawTarget`] by wrapping it in an
+/// [`Image`](embedded_graphics::image::Image). Pixels which match the [`ColorKey`] are skipped
+/// and the remaining pixels in each row are drawn in spans of opaque pixels.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ColorKeyBmp<'a, C> {
+    bmp: Bmp<'a, C>,
+    key: ColorKey<C>,
+}
+
+impl<'a, C> ColorKeyBmp<'a, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    pub(crate) const fn new(bmp: Bmp<'a, C>, key: ColorKey<C>) -> Self {
+        Self { bmp, key }
+    }
+
+    /// Returns a reference to the wrapped BMP image.
+    pub const fn as_bmp(&self) -> &Bmp<'a, C> {
+        &self.bmp
+    }
+
+    /// Returns the color key.
+    pub const fn key(&self) -> ColorKey<C> {
+        self.key
+    }
+
+    /// Converts a raw pixel value into a color.
+    ///
+    /// Returns `None` if the pixel is transparent.
+    fn color(&self, raw: u32) -> Option<C> {
+        let raw_bmp = self.bmp.as_raw();
+        let color = raw_bmp.color_type.to_color(raw, raw_bmp.color_table())?;
+
+        let is_transparent = match self.key {
+            ColorKey::Color(key) => color == key,
+            ColorKey::Index(index) => self.bmp.is_indexed() && raw == u32::from(index),
+        };
+
+        (!is_transparent).then_some(color)
+    }
+
+    /// Draws a part of the image.
+    ///
+    /// The top left corner of `area` is drawn at the origin of `target`. `area` must be inside
+    /// the image bounding box.
+    fn draw_area<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // The colors of indexed images are looked up once per draw.
+        let is_indexed = self.bmp.is_indexed();
+        let color_type = self.bmp.as_raw().color_type;
+        let lookup_table = LookupTable::new(color_type, |index| self.color(u32::from(index)));
+
+        fill_area(self.bmp.as_raw(), target, area, |_, raw| {
+            if is_indexed {
+                lookup_table.get(raw as u8)
+            } else {
+                self.color(raw)
+            }
+        })
+    }
+}
+
+impl<C> ImageDrawable for ColorKeyBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_area(target, &self.bounding_box())
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let source = area.intersection(&self.bounding_box());
+        if source.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.draw_area(
+            &mut target.translated(source.top_left - area.top_left),
+            &source,
+        )
+    }
+}
+
+impl<C> OriginDimensions for ColorKeyBmp<'_, C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.bmp.size()
+    }
+}
+
+impl<C> GetPixel for ColorKeyBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    /// Returns the color of a pixel.
+    ///
+    /// Returns `None` if `p` is outside the image bounding box or if the pixel is transparent.
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        self.color(self.bmp.as_raw().pixel(p)?)
+    }
+}
@@ -1,7 +1,5 @@
-use core::convert::TryInto;
-
 use embedded_graphics::{
-    pixelcolor::{raw::RawU24, Rgb888},
+    pixelcolor::{raw::RawU24, Gray8, Rgb888},
     prelude::*,
 };
 
@@ -19,16 +17,35 @@ use embedded_graphics::{
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ColorTable<'a> {
     data: &'a [u8],
+    entry_len: usize,
 }
 
 impl<'a> ColorTable<'a> {
-    pub(crate) const fn new(data: &'a [u8]) -> Self {
-        Self { data }
+    /// Creates a color table with 3 byte `RGBTRIPLE` or 4 byte `RGBQUAD` entries.
+    pub(crate) const fn with_entry_len(data: &'a [u8], entry_len: usize) -> Self {
+        Self { data, entry_len }
+    }
+
+    /// Returns the raw bytes of the color table.
+    #[cfg(feature = "embedded-io")]
+    pub(crate) const fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the length of a single entry in bytes.
+    #[cfg(feature = "embedded-io")]
+    pub(crate) const fn entry_len(&self) -> usize {
+        self.entry_len
     }
 
     /// Returns the number of entries.
     pub const fn len(&self) -> usize {
-        self.data.len() / 4
+        self.data.len() / self.entry_len
+    }
+
+    /// Returns `true` if the color table contains no entries.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     /// Returns a color table entry.
@@ -37,11 +54,62 @@ impl<'a> ColorTable<'a> {
     pub fn get(&self, index: u32) -> Option<Rgb888> {
         // MSRV: Experiment with slice::as_chunks when it's stabilized
 
-        let offset = index as usize * 4;
-        let bytes = self.data.get(offset..offset + 4)?;
+        let offset = index as usize * self.entry_len;
+        let bytes = self.data.get(offset..offset + 3)?;
 
-        let raw = u32::from_le_bytes(bytes.try_into().unwrap());
+        let raw = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
 
         Some(RawU24::from_u32(raw).into())
     }
+
+    /// Returns a color table entry converted to grayscale.
+    ///
+    /// Gray entries, which have the same value in all color channels, are returned without
+    /// changing their brightness. `None` is returned if `index` is out of bounds.
+    pub fn get_gray8(&self, index: u32) -> Option<Gray8> {
+        self.get(index).map(Gray8::from)
+    }
+
+    /// Returns `true` if all entries are shades of gray.
+    pub fn is_grayscale(&self) -> bool {
+        self.entries()
+            .all(|color| color.r() == color.g() && color.g() == color.b())
+    }
+
+    /// Returns an iterator over all entries.
+    pub(crate) fn entries(&self) -> impl Iterator<Item = Rgb888> + '_ {
+        (0..self.len() as u32).filter_map(|index| self.get(index))
+    }
+}
+
+/// Returns the index of the entry which is closest to `color`.
+///
+/// The distance between two colors is calculated as the squared euclidean distance in the RGB
+/// color space. `None` is returned if `entries` is empty.
+pub(crate) fn nearest_color_index<I>(color: Rgb888, entries: I) -> Option<u32>
+where
+    I: IntoIterator<Item = Rgb888>,
+{
+    let distance = |a: u8, b: u8| {
+        let d = i32::from(a) - i32::from(b);
+        (d * d) as u32
+    };
+
+    let mut nearest = None;
+    for (index, entry) in (0..).zip(entries) {
+        let d = distance(color.r(), entry.r())
+            + distance(color.g(), entry.g())
+            + distance(color.b(), entry.b());
+
+        if d == 0 {
+            return Some(index);
+        }
+
+        match nearest {
+            Some((_, nearest_d)) if nearest_d <= d => {}
+            _ => nearest = Some((index, d)),
+        }
+    }
+
+    nearest.map(|(index, _)| index)
 }
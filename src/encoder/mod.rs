@@ -0,0 +1,649 @@
+//! BMP encoder.
+
+use core::convert::Infallible;
+
+use embedded_graphics::{image::GetPixel, pixelcolor::Rgb888, prelude::*};
+
+use crate::{
+    bitfields::Bitfields, color_table::nearest_color_index, header::dpi_to_pixels_per_meter,
+    BmpFramebuffer, Bpp, ChannelMasks, ColorSpaceInfo, CompressionMethod, Header, RowOrder,
+};
+
+mod rle;
+
+pub use rle::RleEncoder;
+
+/// Size of the BMP file header.
+const FILE_HEADER_SIZE: usize = 14;
+/// Size of a `BITMAPINFOHEADER`.
+const INFO_HEADER_SIZE: usize = 40;
+/// Size of a `BITMAPV4HEADER`, which is used to store channel masks.
+const V4_HEADER_SIZE: usize = 108;
+
+/// `LCS_sRGB` color space type.
+const LCS_SRGB: u32 = 0x7352_4742;
+
+/// Byte sink for the BMP [`Encoder`].
+///
+/// This trait is implemented for `&mut [u8]`, which writes the data to the start of the slice and
+/// advances the slice afterwards.
+pub trait ByteSink {
+    /// Error type.
+    type Error;
+
+    /// Writes all bytes in `data` to the sink.
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl ByteSink for &mut [u8] {
+    type Error = BufferTooSmall;
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        if data.len() > self.len() {
+            return Err(BufferTooSmall);
+        }
+
+        let (head, tail) = core::mem::take(self).split_at_mut(data.len());
+        head.copy_from_slice(data);
+        *self = tail;
+
+        Ok(())
+    }
+}
+
+/// The output buffer is too small to hold the encoded BMP file.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct BufferTooSmall;
+
+/// Encode error.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum EncodeError<E = Infallible> {
+    /// The combination of bit depth, color table and channel masks isn't supported.
+    UnsupportedFormat,
+
+    /// The image has a width or height of zero.
+    InvalidImageDimensions,
+
+    /// The image is too large to be stored in a BMP file.
+    ImageTooLarge,
+
+    /// Writing to the byte sink failed.
+    Write(E),
+}
+
+/// BMP encoder.
+///
+/// The encoder writes images into BMP files without allocating memory. The output is either
+/// written to a [`ByteSink`] or directly into a byte slice.
+///
+/// Indexed images with 1, 4 or 8 bits per pixel require a color table, which can be set by using
+/// [`color_table`](Self::color_table). Colors which aren't contained in the color table are
+/// replaced by the closest entry. 16 bpp images use the `Rgb555` format by default and 32 bpp
+/// images store an unused byte in addition to the 8 bit color channels. Other formats can be
+/// selected by setting the [`channel_masks`](Self::channel_masks).
+///
+/// 4 and 8 bpp images can optionally be stored using RLE compression, see
+/// [`rle_compression`](Self::rle_compression).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{
+///     image::{GetPixel, ImageRawLE},
+///     pixelcolor::Rgb565,
+///     prelude::*,
+/// };
+/// use tinybmp::{Bmp, Bpp, ChannelMasks, Encoder};
+///
+/// let data = [0xF8, 0x00, 0x07, 0xE0, 0x00, 0x1F, 0xFF, 0xFF];
+/// let image = ImageRawLE::<Rgb565>::new(&data, 2);
+///
+/// let encoder = Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks::RGB565);
+///
+/// let mut buffer = [0u8; 256];
+/// let length = encoder.encode_to_slice(&image, &mut buffer).unwrap();
+///
+/// let bmp = Bmp::<Rgb565>::from_slice(&buffer[..length]).unwrap();
+/// assert_eq!(bmp.pixel(Point::new(1, 1)), image.pixel(Point::new(1, 1)));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Encoder<'a> {
+    bpp: Bpp,
+    channel_masks: Option<ChannelMasks>,
+    color_table: &'a [Rgb888],
+    row_order: RowOrder,
+    rle_compression: bool,
+    pixels_per_meter: Size,
+    colors_important: u32,
+}
+
+impl<'a> Encoder<'a> {
+    /// Creates a new encoder.
+    pub const fn new(bpp: Bpp) -> Self {
+        Self {
+            bpp,
+            channel_masks: None,
+            color_table: &[],
+            row_order: RowOrder::BottomUp,
+            rle_compression: false,
+            pixels_per_meter: Size::zero(),
+            colors_important: 0,
+        }
+    }
+
+    /// Sets the color table for indexed images.
+    pub const fn color_table(mut self, color_table: &'a [Rgb888]) -> Self {
+        self.color_table = color_table;
+        self
+    }
+
+    /// Sets the channel masks for 16 and 32 bpp images.
+    ///
+    /// Images with channel masks are stored using the `BI_BITFIELDS` compression method.
+    pub const fn channel_masks(mut self, channel_masks: ChannelMasks) -> Self {
+        self.channel_masks = Some(channel_masks);
+        self
+    }
+
+    /// Sets the row order.
+    ///
+    /// The default row order is [`RowOrder::BottomUp`].
+    pub const fn row_order(mut self, row_order: RowOrder) -> Self {
+        self.row_order = row_order;
+        self
+    }
+
+    /// Enables or disables RLE compression.
+    ///
+    /// RLE compression is only supported for indexed images with 4 or 8 bits per pixel, which are
+    /// stored as RLE4 or RLE8 compressed data. Compressed images are always stored in
+    /// [`RowOrder::BottomUp`] order and can only be encoded by [`encode`](Self::encode) and
+    /// [`encode_to_slice`](Self::encode_to_slice).
+    ///
+    /// See [`RleEncoder`] for more information about the compression.
+    pub const fn rle_compression(mut self, enabled: bool) -> Self {
+        self.rle_compression = enabled;
+        self
+    }
+
+    /// Sets the horizontal and vertical resolution in pixels per meter.
+    ///
+    /// By default the resolution is set to `0`, which means that it isn't specified.
+    pub const fn pixels_per_meter(mut self, pixels_per_meter: Size) -> Self {
+        self.pixels_per_meter = pixels_per_meter;
+        self
+    }
+
+    /// Sets the horizontal and vertical resolution in dots per inch.
+    ///
+    /// The resolution is stored in pixels per meter and is rounded to the nearest integer. See
+    /// [`pixels_per_meter`](Self::pixels_per_meter) for more information.
+    pub const fn dpi(self, dpi: Size) -> Self {
+        self.pixels_per_meter(Size::new(
+            dpi_to_pixels_per_meter(dpi.width),
+            dpi_to_pixels_per_meter(dpi.height),
+        ))
+    }
+
+    /// Sets the number of important colors in the color table.
+    ///
+    /// By default the number is set to `0`, which means that all colors are important.
+    pub const fn colors_important(mut self, colors_important: u32) -> Self {
+        self.colors_important = colors_important;
+        self
+    }
+
+    /// Returns the header of an encoded image with the given size.
+    ///
+    /// The [`file_size`](Header::file_size) field can be used to determine the required buffer
+    /// size for [`encode_to_slice`](Self::encode_to_slice).
+    ///
+    /// The size of RLE compressed images depends on the image content. For these images the
+    /// returned header contains the maximum possible size of the image data and of the file.
+    pub fn header(&self, size: Size) -> Result<Header, EncodeError> {
+        self.raw_format()?;
+
+        if size.width == 0 || size.height == 0 {
+            return Err(EncodeError::InvalidImageDimensions);
+        }
+        if size.width > i32::MAX as u32 || size.height > i32::MAX as u32 {
+            return Err(EncodeError::ImageTooLarge);
+        }
+
+        let dib_header_size = if self.channel_masks.is_some() {
+            V4_HEADER_SIZE
+        } else {
+            INFO_HEADER_SIZE
+        };
+        let image_data_start = FILE_HEADER_SIZE + dib_header_size + self.color_table.len() * 4;
+
+        let mut header = Header {
+            file_size: 0,
+            image_data_start,
+            image_size: size,
+            bpp: self.bpp,
+            image_data_len: 0,
+            channel_masks: self.channel_masks,
+            row_order: self.row_order,
+            compression_method: match (self.bpp, self.rle_compression) {
+                (Bpp::Bits4, true) => CompressionMethod::Rle4,
+                (Bpp::Bits8, true) => CompressionMethod::Rle8,
+                _ if self.channel_masks.is_some() => CompressionMethod::Bitfields,
+                _ => CompressionMethod::Rgb,
+            },
+            pixels_per_meter: self.pixels_per_meter,
+            color_planes: 1,
+            colors_important: self.colors_important,
+            // V4 headers, which are used to store channel masks, use the sRGB color space.
+            color_space: if self.channel_masks.is_some() {
+                Some(ColorSpaceInfo::SRGB)
+            } else {
+                None
+            },
+        };
+
+        let image_data_len = if self.rle_compression {
+            RleEncoder::max_len(size.width, size.height)
+        } else {
+            header.bytes_per_row().checked_mul(size.height as usize)
+        };
+        let image_data_len = image_data_len
+            .and_then(|len| u32::try_from(len).ok())
+            .ok_or(EncodeError::ImageTooLarge)?;
+        let file_size = image_data_len
+            .checked_add(image_data_start as u32)
+            .ok_or(EncodeError::ImageTooLarge)?;
+
+        header.image_data_len = image_data_len;
+        header.file_size = file_size;
+
+        Ok(header)
+    }
+
+    /// Encodes an image.
+    ///
+    /// Pixels for which [`GetPixel::pixel`] returns `None` are stored as black or as color index
+    /// `0` in indexed images.
+    pub fn encode<I, S>(&self, image: &I, sink: &mut S) -> Result<(), EncodeError<S::Error>>
+    where
+        I: GetPixel + OriginDimensions,
+        I::Color: Into<Rgb888>,
+        S: ByteSink,
+    {
+        let size = image.size();
+        let mut header = self.header(size).map_err(EncodeError::cast)?;
+        let format = self.raw_format().map_err(EncodeError::cast)?;
+
+        let color = |x, y| {
+            image
+                .pixel(Point::new(x as i32, y as i32))
+                .map(Into::into)
+                .unwrap_or(Rgb888::BLACK)
+        };
+
+        if self.rle_compression {
+            // The compressed size is required for the header, which is written before the image
+            // data. It is determined by compressing the image twice, because no buffer is
+            // available to store the compressed data.
+            let mut counter = ByteCounter(0);
+            self.write_rle(size, format, color, &mut counter)
+                .map_err(EncodeError::cast)?;
+
+            header.image_data_len = counter.0;
+            header.file_size = counter
+                .0
+                .checked_add(header.image_data_start as u32)
+                .ok_or(EncodeError::ImageTooLarge)?;
+
+            self.write_header(&header, sink)
+                .map_err(EncodeError::Write)?;
+            return self.write_rle(size, format, color, sink);
+        }
+
+        self.write_header(&header, sink)
+            .map_err(EncodeError::Write)?;
+
+        for row in 0..size.height {
+            let y = match self.row_order {
+                RowOrder::TopDown => row,
+                RowOrder::BottomUp => size.height - 1 - row,
+            };
+
+            let mut writer = RowWriter::new(self.bpp);
+            for x in 0..size.width {
+                writer.push(format.raw(color(x, y)), sink)?;
+            }
+            writer.finish(sink)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes an image from an iterator over pixel colors.
+    ///
+    /// The iterator must return the colors of all pixels in the image row by row, starting at the
+    /// top left corner. Missing pixels at the end of the iterator are stored as black or as color
+    /// index `0` in indexed images.
+    ///
+    /// The image is always stored in [`RowOrder::TopDown`] order, regardless of the row order set
+    /// by [`row_order`](Self::row_order), because bottom-up images can't be written sequentially.
+    /// For the same reason RLE compression isn't supported by this method.
+    pub fn encode_pixels<P, S>(
+        &self,
+        size: Size,
+        pixels: P,
+        sink: &mut S,
+    ) -> Result<(), EncodeError<S::Error>>
+    where
+        P: IntoIterator,
+        P::Item: Into<Rgb888>,
+        S: ByteSink,
+    {
+        if self.rle_compression {
+            return Err(EncodeError::UnsupportedFormat);
+        }
+
+        let encoder = self.row_order(RowOrder::TopDown);
+        let header = encoder.header(size).map_err(EncodeError::cast)?;
+        let format = encoder.raw_format().map_err(EncodeError::cast)?;
+
+        encoder
+            .write_header(&header, sink)
+            .map_err(EncodeError::Write)?;
+
+        let mut pixels = pixels.into_iter().map(Into::into);
+        for _ in 0..size.height {
+            let mut writer = RowWriter::new(self.bpp);
+            for _ in 0..size.width {
+                let raw = pixels.next().map_or(0, |color| format.raw(color));
+                writer.push(raw, sink)?;
+            }
+            writer.finish(sink)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes an image into a byte slice.
+    ///
+    /// Returns the length of the encoded BMP file.
+    pub fn encode_to_slice<I>(
+        &self,
+        image: &I,
+        buffer: &mut [u8],
+    ) -> Result<usize, EncodeError<BufferTooSmall>>
+    where
+        I: GetPixel + OriginDimensions,
+        I::Color: Into<Rgb888>,
+    {
+        let length = buffer.len();
+
+        let mut sink = buffer;
+        self.encode(image, &mut sink)?;
+
+        Ok(length - sink.len())
+    }
+
+    /// Encodes an image drawable into a byte slice.
+    ///
+    /// Unlike [`encode_to_slice`](Self::encode_to_slice) this method only requires the image to
+    /// implement [`ImageDrawable`]. The image is drawn directly into the pixel data of the output
+    /// buffer. Pixels which aren't drawn by the image are stored as color index `0` or as black.
+    /// RLE compression isn't supported by this method.
+    ///
+    /// Returns the length of the encoded BMP file.
+    pub fn encode_drawable_to_slice<I>(
+        &self,
+        image: &I,
+        buffer: &mut [u8],
+    ) -> Result<usize, EncodeError<BufferTooSmall>>
+    where
+        I: ImageDrawable,
+        I::Color: Into<Rgb888>,
+    {
+        let mut framebuffer = self.framebuffer(image.size(), buffer)?;
+        image.draw(&mut framebuffer).unwrap_or_else(|e| match e {});
+
+        Ok(framebuffer.as_raw().header().file_size as usize)
+    }
+
+    /// Creates a framebuffer in a byte slice.
+    ///
+    /// The BMP header and color table are written to the start of the slice and the image data is
+    /// initialized to color index `0` or black. The returned [`BmpFramebuffer`] can be used to draw
+    /// into the image data, while the slice always contains a valid BMP file.
+    ///
+    /// RLE compressed framebuffers aren't supported and return
+    /// [`EncodeError::UnsupportedFormat`].
+    pub fn framebuffer<'b, C>(
+        &self,
+        size: Size,
+        buffer: &'b mut [u8],
+    ) -> Result<BmpFramebuffer<'b, C>, EncodeError<BufferTooSmall>>
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        if self.rle_compression {
+            return Err(EncodeError::UnsupportedFormat);
+        }
+
+        let header = self.header(size).map_err(EncodeError::cast)?;
+
+        let buffer = buffer
+            .get_mut(..header.file_size as usize)
+            .ok_or(EncodeError::Write(BufferTooSmall))?;
+        let (header_data, image_data) = buffer.split_at_mut(header.image_data_start);
+
+        let mut sink = header_data;
+        self.write_header(&header, &mut sink)
+            .map_err(EncodeError::Write)?;
+        image_data.fill(0);
+
+        // Formats which aren't supported by `BmpFramebuffer` are reported as unsupported.
+        BmpFramebuffer::from_slice(buffer).map_err(|_| EncodeError::UnsupportedFormat)
+    }
+
+    /// Writes RLE compressed image data.
+    fn write_rle<F, S>(
+        &self,
+        size: Size,
+        format: RawFormat<'_>,
+        color: F,
+        sink: &mut S,
+    ) -> Result<(), EncodeError<S::Error>>
+    where
+        F: Fn(u32, u32) -> Rgb888,
+        S: ByteSink,
+    {
+        let mut encoder = RleEncoder::new(self.bpp).map_err(EncodeError::cast)?;
+
+        for y in (0..size.height).rev() {
+            for x in 0..size.width {
+                encoder.push(format.raw(color(x, y)) as u8, sink)?;
+            }
+            encoder.end_row(sink)?;
+        }
+
+        encoder.end_bitmap(sink)
+    }
+
+    /// Returns the raw color format.
+    fn raw_format(&self) -> Result<RawFormat<'a>, EncodeError> {
+        let is_indexed = matches!(self.bpp, Bpp::Bits1 | Bpp::Bits2 | Bpp::Bits4 | Bpp::Bits8);
+        if is_indexed
+            && (self.color_table.is_empty() || self.color_table.len() > 1 << self.bpp.bits())
+        {
+            return Err(EncodeError::UnsupportedFormat);
+        }
+        if !is_indexed && !self.color_table.is_empty() {
+            return Err(EncodeError::UnsupportedFormat);
+        }
+        if self.rle_compression
+            && (!matches!(self.bpp, Bpp::Bits4 | Bpp::Bits8)
+                || self.row_order != RowOrder::BottomUp)
+        {
+            return Err(EncodeError::UnsupportedFormat);
+        }
+
+        Ok(match (self.bpp, self.channel_masks) {
+            (Bpp::Bits1 | Bpp::Bits2 | Bpp::Bits4 | Bpp::Bits8, None) => {
+                RawFormat::Indexed(self.color_table)
+            }
+            (Bpp::Bits16, None) => RawFormat::Bitfields(
+                Bitfields::new(&ChannelMasks::RGB555, 16)
+                    .map_err(|_| EncodeError::UnsupportedFormat)?,
+            ),
+            (Bpp::Bits16 | Bpp::Bits32, Some(masks)) => RawFormat::Bitfields(
+                Bitfields::new(&masks, self.bpp.bits())
+                    .map_err(|_| EncodeError::UnsupportedFormat)?,
+            ),
+            (Bpp::Bits24 | Bpp::Bits32, None) => RawFormat::Rgb888,
+            _ => return Err(EncodeError::UnsupportedFormat),
+        })
+    }
+
+    /// Writes the file header, DIB header and color table.
+    fn write_header<S: ByteSink>(&self, header: &Header, sink: &mut S) -> Result<(), S::Error> {
+        let dib_header_size =
+            header.image_data_start - FILE_HEADER_SIZE - self.color_table.len() * 4;
+        let height = match header.row_order {
+            RowOrder::TopDown => -(header.image_size.height as i32),
+            RowOrder::BottomUp => header.image_size.height as i32,
+        };
+
+        // File header
+        sink.write_all(b"BM")?;
+        sink.write_all(&header.file_size.to_le_bytes())?;
+        sink.write_all(&[0; 4])?;
+        sink.write_all(&(header.image_data_start as u32).to_le_bytes())?;
+
+        // DIB header
+        sink.write_all(&(dib_header_size as u32).to_le_bytes())?;
+        sink.write_all(&(header.image_size.width as i32).to_le_bytes())?;
+        sink.write_all(&height.to_le_bytes())?;
+        sink.write_all(&header.color_planes.to_le_bytes())?;
+        sink.write_all(&header.bpp.bits().to_le_bytes())?;
+        sink.write_all(&header.compression_method.value().to_le_bytes())?;
+        sink.write_all(&header.image_data_len.to_le_bytes())?;
+        sink.write_all(&header.pixels_per_meter.width.to_le_bytes())?;
+        sink.write_all(&header.pixels_per_meter.height.to_le_bytes())?;
+        sink.write_all(&(self.color_table.len() as u32).to_le_bytes())?;
+        sink.write_all(&header.colors_important.to_le_bytes())?;
+
+        if let Some(masks) = header.channel_masks {
+            sink.write_all(&masks.red.to_le_bytes())?;
+            sink.write_all(&masks.green.to_le_bytes())?;
+            sink.write_all(&masks.blue.to_le_bytes())?;
+            sink.write_all(&masks.alpha.to_le_bytes())?;
+            sink.write_all(&LCS_SRGB.to_le_bytes())?;
+            // CIE XYZ endpoints and gamma values, which are unused for sRGB.
+            sink.write_all(&[0; 36 + 12])?;
+        }
+
+        for color in self.color_table {
+            sink.write_all(&[color.b(), color.g(), color.r(), 0])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl EncodeError {
+    /// Converts a format error into an error with a different write error type.
+    fn cast<E>(self) -> EncodeError<E> {
+        match self {
+            EncodeError::UnsupportedFormat => EncodeError::UnsupportedFormat,
+            EncodeError::InvalidImageDimensions => EncodeError::InvalidImageDimensions,
+            EncodeError::ImageTooLarge => EncodeError::ImageTooLarge,
+            EncodeError::Write(e) => match e {},
+        }
+    }
+}
+
+/// Byte sink which only counts the number of written bytes.
+struct ByteCounter(u32);
+
+impl ByteSink for ByteCounter {
+    type Error = Infallible;
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.0 += data.len() as u32;
+        Ok(())
+    }
+}
+
+/// Raw color format of the encoded image.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum RawFormat<'a> {
+    Indexed(&'a [Rgb888]),
+    Bitfields(Bitfields),
+    Rgb888,
+}
+
+impl RawFormat<'_> {
+    /// Converts a color into a raw pixel value.
+    pub(crate) fn raw(&self, color: Rgb888) -> u32 {
+        match self {
+            RawFormat::Indexed(color_table) => {
+                nearest_color_index(color, color_table.iter().copied()).unwrap_or(0)
+            }
+            RawFormat::Bitfields(bitfields) => bitfields.raw_from_rgb888(color),
+            RawFormat::Rgb888 => color.into_storage(),
+        }
+    }
+}
+
+/// Writes a single row of raw pixel values, including the padding at the end of the row.
+struct RowWriter {
+    bpp: u16,
+    /// Pixels with less than 8 bits per pixel, which haven't been written yet.
+    partial_byte: u8,
+    partial_bits: u16,
+    /// Number of bytes written.
+    length: usize,
+}
+
+impl RowWriter {
+    const fn new(bpp: Bpp) -> Self {
+        Self {
+            bpp: bpp.bits(),
+            partial_byte: 0,
+            partial_bits: 0,
+            length: 0,
+        }
+    }
+
+    fn push<S: ByteSink>(&mut self, raw: u32, sink: &mut S) -> Result<(), EncodeError<S::Error>> {
+        if self.bpp < 8 {
+            self.partial_byte |= (raw as u8) << (8 - self.bpp - self.partial_bits);
+            self.partial_bits += self.bpp;
+            if self.partial_bits == 8 {
+                self.write(&[self.partial_byte], sink)?;
+                self.partial_byte = 0;
+                self.partial_bits = 0;
+            }
+            Ok(())
+        } else {
+            let bytes = raw.to_le_bytes();
+            self.write(&bytes[..usize::from(self.bpp / 8)], sink)
+        }
+    }
+
+    fn finish<S: ByteSink>(mut self, sink: &mut S) -> Result<(), EncodeError<S::Error>> {
+        if self.partial_bits > 0 {
+            self.write(&[self.partial_byte], sink)?;
+        }
+
+        let padding = self.length.wrapping_neg() % 4;
+        self.write(&[0; 3][..padding], sink)
+    }
+
+    fn write<S: ByteSink>(
+        &mut self,
+        data: &[u8],
+        sink: &mut S,
+    ) -> Result<(), EncodeError<S::Error>> {
+        self.length += data.len();
+        sink.write_all(data).map_err(EncodeError::Write)
+    }
+}
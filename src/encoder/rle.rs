@@ -0,0 +1,338 @@
+//! RLE8 and RLE4 compression.
+
+use crate::{
+    encoder::{ByteSink, EncodeError},
+    Bpp,
+};
+
+/// Maximum number of pixels in a single encoded run or absolute mode sequence.
+const MAX_LENGTH: usize = 255;
+
+/// Minimum number of pixels in an encoded run.
+///
+/// Shorter runs are stored in absolute mode, because an encoded run would interrupt the current
+/// absolute mode sequence, which costs more bytes than it saves.
+const MIN_RUN_LENGTH: u8 = 3;
+
+/// Minimum number of pixels in an absolute mode sequence.
+///
+/// Absolute mode lengths of `1` and `2` are used as escape codes for end of bitmap and delta.
+const MIN_ABSOLUTE_LENGTH: usize = 3;
+
+/// Run-length encoder for RLE8 and RLE4 compressed image data.
+///
+/// Pixel indices are added row by row by calling [`push`](Self::push), which writes the compressed
+/// data to a [`ByteSink`] as soon as possible. Rows must be ended with [`end_row`](Self::end_row)
+/// and the bitmap with [`end_bitmap`](Self::end_bitmap). Because RLE compressed BMP files are
+/// always stored bottom-up, the rows must be added starting at the bottom of the image.
+///
+/// Repeated pixels are stored as encoded runs and all other pixels are collected into absolute
+/// mode sequences. In RLE4 images encoded runs can also contain two alternating pixel values.
+///
+/// This encoder is used by [`Encoder`](crate::Encoder) if RLE compression is enabled, but it can
+/// also be used directly to compress image data which is already available as color indices.
+///
+/// # Examples
+///
+/// ```
+/// use tinybmp::{Bpp, RleEncoder};
+///
+/// let mut data = [0u8; 32];
+/// let mut sink = &mut data[..];
+///
+/// let mut encoder = RleEncoder::new(Bpp::Bits8).unwrap();
+/// for index in [7, 7, 7, 7, 1, 2, 3] {
+///     encoder.push(index, &mut sink).unwrap();
+/// }
+/// encoder.end_row(&mut sink).unwrap();
+/// encoder.end_bitmap(&mut sink).unwrap();
+///
+/// assert_eq!(data[..12], [4, 7, 0, 3, 1, 2, 3, 0, 0, 0, 0, 1]);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RleEncoder {
+    /// `true` for RLE4 and `false` for RLE8.
+    is_rle4: bool,
+
+    /// Pixels which will be stored in absolute mode.
+    absolute: [u8; MAX_LENGTH],
+    absolute_length: usize,
+
+    /// Pixel values of the current run.
+    ///
+    /// Runs in RLE4 images alternate between both values, RLE8 runs only use the first value.
+    run: [u8; 2],
+    run_length: u8,
+}
+
+impl RleEncoder {
+    /// Creates a new RLE encoder.
+    ///
+    /// 8 bpp images are compressed using RLE8 and 4 bpp images using RLE4. Other bit depths
+    /// return [`EncodeError::UnsupportedFormat`].
+    pub const fn new(bpp: Bpp) -> Result<Self, EncodeError> {
+        let is_rle4 = match bpp {
+            Bpp::Bits4 => true,
+            Bpp::Bits8 => false,
+            _ => return Err(EncodeError::UnsupportedFormat),
+        };
+
+        Ok(Self {
+            is_rle4,
+            absolute: [0; MAX_LENGTH],
+            absolute_length: 0,
+            run: [0; 2],
+            run_length: 0,
+        })
+    }
+
+    /// Adds a pixel to the current row.
+    ///
+    /// For RLE4 images only the lower 4 bits of `index` are used.
+    pub fn push<S: ByteSink>(
+        &mut self,
+        index: u8,
+        sink: &mut S,
+    ) -> Result<(), EncodeError<S::Error>> {
+        let index = if self.is_rle4 { index & 0x0F } else { index };
+
+        let continues_run = match self.run_length {
+            0 => false,
+            1 if self.is_rle4 => {
+                self.run[1] = index;
+                true
+            }
+            length => {
+                usize::from(length) < MAX_LENGTH
+                    && index == self.run[usize::from(length % 2) & usize::from(self.is_rle4)]
+            }
+        };
+
+        if continues_run {
+            self.run_length += 1;
+        } else {
+            self.end_run(sink)?;
+            self.run = [index; 2];
+            self.run_length = 1;
+        }
+
+        Ok(())
+    }
+
+    /// Ends the current row.
+    ///
+    /// All pending pixels are written to the sink, followed by an end of line marker.
+    pub fn end_row<S: ByteSink>(&mut self, sink: &mut S) -> Result<(), EncodeError<S::Error>> {
+        self.end_run(sink)?;
+        self.write_absolute(sink)?;
+
+        sink.write_all(&[0, 0]).map_err(EncodeError::Write)
+    }
+
+    /// Ends the bitmap.
+    ///
+    /// All pending pixels are written to the sink, followed by an end of bitmap marker. Pending
+    /// pixels which weren't ended by [`end_row`](Self::end_row) are not followed by an end of line
+    /// marker.
+    pub fn end_bitmap<S: ByteSink>(&mut self, sink: &mut S) -> Result<(), EncodeError<S::Error>> {
+        self.end_run(sink)?;
+        self.write_absolute(sink)?;
+
+        sink.write_all(&[0, 1]).map_err(EncodeError::Write)
+    }
+
+    /// Returns the maximum length of the compressed data for an image with the given size.
+    ///
+    /// The returned length includes the end of line markers after each row and the end of bitmap
+    /// marker.
+    pub const fn max_len(width: u32, height: u32) -> Option<usize> {
+        // Each pixel uses at most two bytes, which is the worst case for pixels stored as encoded
+        // runs with a length of `1`. Absolute mode sequences are always shorter than that.
+        let row_len = match (width as usize).checked_mul(2) {
+            Some(len) => len + 2,
+            None => return None,
+        };
+
+        match row_len.checked_mul(height as usize) {
+            Some(len) => len.checked_add(2),
+            None => None,
+        }
+    }
+
+    /// Ends the current run.
+    ///
+    /// Long runs are written as encoded runs and short runs are added to the absolute mode buffer.
+    fn end_run<S: ByteSink>(&mut self, sink: &mut S) -> Result<(), EncodeError<S::Error>> {
+        let run_length = core::mem::take(&mut self.run_length);
+
+        if run_length >= MIN_RUN_LENGTH {
+            self.write_absolute(sink)?;
+
+            let value = if self.is_rle4 {
+                (self.run[0] << 4) | self.run[1]
+            } else {
+                self.run[0]
+            };
+            sink.write_all(&[run_length, value])
+                .map_err(EncodeError::Write)
+        } else {
+            for i in 0..run_length {
+                if self.absolute_length == MAX_LENGTH {
+                    self.write_absolute(sink)?;
+                }
+
+                self.absolute[self.absolute_length] =
+                    self.run[usize::from(i % 2) & usize::from(self.is_rle4)];
+                self.absolute_length += 1;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Writes the pixels in the absolute mode buffer.
+    fn write_absolute<S: ByteSink>(&mut self, sink: &mut S) -> Result<(), EncodeError<S::Error>> {
+        let pixels = &self.absolute[..core::mem::take(&mut self.absolute_length)];
+
+        if pixels.len() < MIN_ABSOLUTE_LENGTH {
+            // Sequences which are too short for absolute mode are written as runs of single pixels.
+            for &index in pixels {
+                let value = if self.is_rle4 { index << 4 } else { index };
+                sink.write_all(&[1, value]).map_err(EncodeError::Write)?;
+            }
+
+            return Ok(());
+        }
+
+        sink.write_all(&[0, pixels.len() as u8])
+            .map_err(EncodeError::Write)?;
+
+        let mut length = 0;
+        if self.is_rle4 {
+            for pair in pixels.chunks(2) {
+                let byte = (pair[0] << 4) | pair.get(1).copied().unwrap_or(0);
+                sink.write_all(&[byte]).map_err(EncodeError::Write)?;
+                length += 1;
+            }
+        } else {
+            sink.write_all(pixels).map_err(EncodeError::Write)?;
+            length = pixels.len();
+        }
+
+        // Absolute mode sequences are padded to a 16 bit boundary.
+        if length % 2 != 0 {
+            sink.write_all(&[0]).map_err(EncodeError::Write)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(bpp: Bpp, rows: &[&[u8]]) -> ([u8; 1024], usize) {
+        let mut data = [0; 1024];
+        let mut sink = &mut data[..];
+
+        let mut encoder = RleEncoder::new(bpp).unwrap();
+        for row in rows {
+            for &index in row.iter() {
+                encoder.push(index, &mut sink).unwrap();
+            }
+            encoder.end_row(&mut sink).unwrap();
+        }
+        encoder.end_bitmap(&mut sink).unwrap();
+
+        let len = 1024 - sink.len();
+        (data, len)
+    }
+
+    #[track_caller]
+    fn assert_encoded(bpp: Bpp, rows: &[&[u8]], expected: &[u8]) {
+        let (data, len) = encode(bpp, rows);
+        assert_eq!(&data[..len], expected);
+    }
+
+    #[test]
+    fn rle8_runs() {
+        assert_encoded(
+            Bpp::Bits8,
+            &[&[1, 1, 1, 2, 2, 2, 2], &[3; 300]],
+            &[3, 1, 4, 2, 0, 0, 255, 3, 45, 3, 0, 0, 0, 1],
+        );
+    }
+
+    #[test]
+    fn rle8_absolute() {
+        // Odd lengths are padded.
+        assert_encoded(
+            Bpp::Bits8,
+            &[&[1, 2, 3, 3, 4]],
+            &[0, 5, 1, 2, 3, 3, 4, 0, 0, 0, 0, 1],
+        );
+        assert_encoded(
+            Bpp::Bits8,
+            &[&[1, 2, 3, 4, 5, 5, 5, 5]],
+            &[0, 4, 1, 2, 3, 4, 4, 5, 0, 0, 0, 1],
+        );
+    }
+
+    #[test]
+    fn rle8_short_sequences() {
+        assert_encoded(
+            Bpp::Bits8,
+            &[&[1, 2, 3, 3, 3], &[9]],
+            &[1, 1, 1, 2, 3, 3, 0, 0, 1, 9, 0, 0, 0, 1],
+        );
+    }
+
+    #[test]
+    fn rle8_long_absolute() {
+        let row: [u8; 256] = core::array::from_fn(|i| i as u8);
+        let (data, len) = encode(Bpp::Bits8, &[&row]);
+
+        assert_eq!(len, 2 + 255 + 1 + 2 + 2 + 2);
+        assert_eq!(&data[..3], &[0, 255, 0]);
+        assert_eq!(&data[256..len], &[254, 0, 1, 255, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn rle4_runs() {
+        assert_encoded(
+            Bpp::Bits4,
+            &[&[1, 2, 1, 2, 1, 3, 3, 3, 3]],
+            &[5, 0x12, 4, 0x33, 0, 0, 0, 1],
+        );
+    }
+
+    #[test]
+    fn rle4_absolute() {
+        // 6 pixels use 3 bytes, which requires a padding byte.
+        assert_encoded(
+            Bpp::Bits4,
+            &[&[1, 2, 3, 4, 4, 5]],
+            &[0, 6, 0x12, 0x34, 0x45, 0, 0, 0, 0, 1],
+        );
+        assert_encoded(
+            Bpp::Bits4,
+            &[&[1, 2, 3, 3, 1, 2]],
+            &[0, 6, 0x12, 0x33, 0x12, 0, 0, 0, 0, 1],
+        );
+    }
+
+    #[test]
+    fn max_len() {
+        assert_eq!(RleEncoder::max_len(10, 2), Some(2 * 22 + 2));
+        assert_eq!(RleEncoder::max_len(u32::MAX, u32::MAX), None);
+    }
+
+    #[test]
+    fn unsupported_bpp() {
+        assert_eq!(
+            RleEncoder::new(Bpp::Bits24),
+            Err(EncodeError::UnsupportedFormat)
+        );
+    }
+}
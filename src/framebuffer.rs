@@ -0,0 +1,152 @@
+use core::{convert::Infallible, marker::PhantomData};
+
+use embedded_graphics::{
+    pixelcolor::{Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{Bmp, ParseError, RawBmpMut};
+
+/// Draw target which renders directly into an in-memory BMP file.
+///
+/// Drawing operations update the pixel data of the wrapped BMP file in place, which means that the
+/// byte slice always contains a valid BMP file. This makes it possible to send the framebuffer
+/// content to another device or to save it to a file without an extra conversion step.
+///
+/// Colors are converted into the pixel format of the BMP file. Colors in indexed images are
+/// replaced by the index of the closest color table entry. Only uncompressed images are supported.
+///
+/// An empty framebuffer can be created by using [`Encoder::framebuffer`](crate::Encoder::framebuffer).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{
+///     image::GetPixel,
+///     pixelcolor::Rgb565,
+///     prelude::*,
+///     primitives::{PrimitiveStyle, Rectangle},
+/// };
+/// use tinybmp::{Bpp, Encoder};
+///
+/// let mut buffer = [0u8; 256];
+/// let encoder = Encoder::new(Bpp::Bits16);
+/// let mut framebuffer = encoder
+///     .framebuffer::<Rgb565>(Size::new(8, 8), &mut buffer)
+///     .unwrap();
+///
+/// Rectangle::new(Point::new(2, 2), Size::new(4, 4))
+///     .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+///     .draw(&mut framebuffer)
+///     .unwrap();
+///
+/// assert_eq!(framebuffer.as_bmp().pixel(Point::new(3, 3)), Some(Rgb565::RED));
+/// assert_eq!(framebuffer.as_bmp().pixel(Point::new(1, 1)), Some(Rgb565::BLACK));
+///
+/// // The buffer contains a valid BMP file.
+/// let file_size = framebuffer.as_raw().header().file_size as usize;
+/// assert_eq!(&buffer[0..2], b"BM");
+/// # assert_eq!(file_size, 14 + 40 + 8 * 8 * 2);
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct BmpFramebuffer<'a, C> {
+    raw_bmp: RawBmpMut<'a>,
+    color_type: PhantomData<C>,
+}
+
+impl<'a, C> BmpFramebuffer<'a, C>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    /// Creates a framebuffer from a byte slice which contains a BMP file.
+    ///
+    /// Returns [`ParseError::UnsupportedCompressionMethod`] if the image is RLE compressed or
+    /// contains an embedded JPEG or PNG image.
+    pub fn from_slice(bytes: &'a mut [u8]) -> Result<Self, ParseError> {
+        RawBmpMut::from_slice(bytes).map(Self::from_raw)
+    }
+
+    /// Creates a framebuffer from a mutable raw BMP image.
+    pub fn from_raw(raw_bmp: RawBmpMut<'a>) -> Self {
+        Self {
+            raw_bmp,
+            color_type: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the raw BMP image.
+    pub fn as_raw(&self) -> &RawBmpMut<'a> {
+        &self.raw_bmp
+    }
+
+    /// Returns a mutable reference to the raw BMP image.
+    pub fn as_raw_mut(&mut self) -> &mut RawBmpMut<'a> {
+        &mut self.raw_bmp
+    }
+
+    /// Returns the wrapped raw BMP image.
+    pub fn into_raw(self) -> RawBmpMut<'a> {
+        self.raw_bmp
+    }
+
+    /// Converts a color into a raw pixel value.
+    fn raw(&self, color: C) -> u32 {
+        self.raw_bmp
+            .color_type
+            .raw_from_rgb888(color.into(), self.raw_bmp.color_table())
+    }
+}
+
+impl<C> BmpFramebuffer<'_, C>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    /// Returns a BMP image which can be used to read the framebuffer content.
+    pub fn as_bmp(&self) -> Bmp<'_, C> {
+        Bmp {
+            raw_bmp: self.raw_bmp.as_raw(),
+            color_type: PhantomData,
+        }
+    }
+}
+
+impl<C> DrawTarget for BmpFramebuffer<'_, C>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            let raw = self.raw(color);
+            self.raw_bmp.set_pixel(p, raw);
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        // Convert the color only once, which avoids repeated color table lookups.
+        let raw = self.raw(color);
+        for p in area.intersection(&self.bounding_box()).points() {
+            self.raw_bmp.set_pixel(p, raw);
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid(&self.bounding_box(), color)
+    }
+}
+
+impl<C> OriginDimensions for BmpFramebuffer<'_, C> {
+    fn size(&self) -> Size {
+        self.raw_bmp.header().image_size
+    }
+}
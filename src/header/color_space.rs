@@ -0,0 +1,249 @@
+//! Color space information in V4 and V5 headers.
+
+use crate::{
+    parser::{le_u32, take_slice},
+    try_const, ParseError,
+};
+
+/// `LCS_CALIBRATED_RGB` color space type.
+const LCS_CALIBRATED_RGB: u32 = 0;
+/// `LCS_sRGB` color space type.
+const LCS_SRGB: u32 = 0x7352_4742;
+/// `LCS_WINDOWS_COLOR_SPACE` color space type.
+const LCS_WINDOWS_COLOR_SPACE: u32 = 0x5769_6E20;
+/// `PROFILE_LINKED` color space type.
+const PROFILE_LINKED: u32 = 0x4C49_4E4B;
+/// `PROFILE_EMBEDDED` color space type.
+const PROFILE_EMBEDDED: u32 = 0x4D42_4544;
+
+/// Color space information.
+///
+/// Color space information is only stored in `BITMAPV4HEADER` and `BITMAPV5HEADER` headers, see
+/// [`Header::color_space`](crate::Header::color_space).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ColorSpaceInfo {
+    /// Color space type.
+    pub color_space: ColorSpace,
+
+    /// CIE XYZ coordinates of the red, green and blue endpoints.
+    ///
+    /// The endpoints are only used by the [`ColorSpace::CalibratedRgb`] color space.
+    pub endpoints: [CieXyz; 3],
+
+    /// Gamma values for the red, green and blue channels.
+    ///
+    /// The values are stored as unsigned 16.16 fixed point numbers and are only used by the
+    /// [`ColorSpace::CalibratedRgb`] color space.
+    pub gamma: [u32; 3],
+
+    /// Rendering intent.
+    ///
+    /// The rendering intent is only stored in `BITMAPV5HEADER` headers and is `None` for
+    /// `BITMAPV4HEADER` headers.
+    pub intent: Option<RenderingIntent>,
+
+    /// Offset of the ICC profile data in bytes, relative to the start of the DIB header.
+    ///
+    /// The profile is only used by the [`ColorSpace::LinkedProfile`] and
+    /// [`ColorSpace::EmbeddedProfile`] color spaces and is only stored in `BITMAPV5HEADER`
+    /// headers. See [`RawBmp::icc_profile`](crate::RawBmp::icc_profile) to access the profile.
+    pub profile_offset: u32,
+
+    /// Length of the ICC profile data in bytes.
+    pub profile_size: u32,
+}
+
+impl ColorSpaceInfo {
+    /// sRGB color space without a rendering intent.
+    pub(crate) const SRGB: Self = Self {
+        color_space: ColorSpace::Srgb,
+        endpoints: [CieXyz { x: 0, y: 0, z: 0 }; 3],
+        gamma: [0; 3],
+        intent: None,
+        profile_offset: 0,
+        profile_size: 0,
+    };
+
+    /// Parses the color space fields of a V4 or V5 header.
+    ///
+    /// `input` must start at the color space type, directly after the channel masks. The rendering
+    /// intent and the profile location are only parsed if `has_v5_fields` is `true`.
+    pub(crate) const fn parse(input: &[u8], has_v5_fields: bool) -> Result<Self, ParseError> {
+        let (mut input, color_space) = try_const!(le_u32(input));
+
+        // Endpoints, followed by the gamma values.
+        let mut values = [0; 12];
+        let mut i = 0;
+        while i < values.len() {
+            (input, values[i]) = try_const!(le_u32(input));
+            i += 1;
+        }
+
+        let (intent, profile_offset, profile_size) = if has_v5_fields {
+            let (input, intent) = try_const!(le_u32(input));
+            let (input, profile_offset) = try_const!(le_u32(input));
+            let (_input, profile_size) = try_const!(le_u32(input));
+            (
+                Some(RenderingIntent::from_u32(intent)),
+                profile_offset,
+                profile_size,
+            )
+        } else {
+            (None, 0, 0)
+        };
+
+        Ok(Self {
+            color_space: ColorSpace::from_u32(color_space),
+            endpoints: [
+                CieXyz::new(values[0], values[1], values[2]),
+                CieXyz::new(values[3], values[4], values[5]),
+                CieXyz::new(values[6], values[7], values[8]),
+            ],
+            gamma: [values[9], values[10], values[11]],
+            intent,
+            profile_offset,
+            profile_size,
+        })
+    }
+}
+
+/// ICC color profile.
+///
+/// See [`RawBmp::icc_profile`](crate::RawBmp::icc_profile) for more information.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum IccProfile<'a> {
+    /// Embedded ICC profile data.
+    Embedded(&'a [u8]),
+
+    /// File name of a linked ICC profile.
+    ///
+    /// The file name is stored in the Windows-1252 character set and doesn't include the
+    /// terminating null character.
+    Linked(&'a [u8]),
+}
+
+impl<'a> IccProfile<'a> {
+    /// Returns the ICC profile, which starts at `offset` in `bytes`.
+    ///
+    /// `None` is returned if the image doesn't use a profile or if the profile data isn't
+    /// contained in `bytes`.
+    pub(crate) const fn parse(
+        bytes: &'a [u8],
+        offset: usize,
+        color_space: &ColorSpaceInfo,
+    ) -> Option<Self> {
+        let is_linked = match color_space.color_space {
+            ColorSpace::LinkedProfile => true,
+            ColorSpace::EmbeddedProfile => false,
+            _ => return None,
+        };
+
+        let Ok((input, _)) = take_slice(bytes, offset) else {
+            return None;
+        };
+        let Ok((_, data)) = take_slice(input, color_space.profile_size as usize) else {
+            return None;
+        };
+
+        if !is_linked {
+            return Some(Self::Embedded(data));
+        }
+
+        // The file name is terminated by a null character.
+        let mut len = 0;
+        while len < data.len() && data[len] != 0 {
+            len += 1;
+        }
+        let (file_name, _) = data.split_at(len);
+
+        Some(Self::Linked(file_name))
+    }
+}
+
+/// Color space type.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ColorSpace {
+    /// Calibrated RGB (`LCS_CALIBRATED_RGB`).
+    ///
+    /// The color space is defined by the endpoints and gamma values in [`ColorSpaceInfo`].
+    CalibratedRgb,
+
+    /// sRGB color space (`LCS_sRGB`).
+    Srgb,
+
+    /// Default color space of the system (`LCS_WINDOWS_COLOR_SPACE`).
+    WindowsColorSpace,
+
+    /// Color space is defined by a linked ICC profile (`PROFILE_LINKED`).
+    LinkedProfile,
+
+    /// Color space is defined by an embedded ICC profile (`PROFILE_EMBEDDED`).
+    EmbeddedProfile,
+
+    /// Unknown color space type.
+    Unknown(u32),
+}
+
+impl ColorSpace {
+    const fn from_u32(value: u32) -> Self {
+        match value {
+            LCS_CALIBRATED_RGB => Self::CalibratedRgb,
+            LCS_SRGB => Self::Srgb,
+            LCS_WINDOWS_COLOR_SPACE => Self::WindowsColorSpace,
+            PROFILE_LINKED => Self::LinkedProfile,
+            PROFILE_EMBEDDED => Self::EmbeddedProfile,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// CIE XYZ color coordinates.
+///
+/// The coordinates are stored as unsigned 2.30 fixed point numbers.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct CieXyz {
+    /// X coordinate.
+    pub x: u32,
+    /// Y coordinate.
+    pub y: u32,
+    /// Z coordinate.
+    pub z: u32,
+}
+
+impl CieXyz {
+    /// Creates new CIE XYZ color coordinates.
+    pub const fn new(x: u32, y: u32, z: u32) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// Rendering intent.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum RenderingIntent {
+    /// Maintains saturation (`LCS_GM_BUSINESS`).
+    Saturation,
+
+    /// Maintains colorimetric match (`LCS_GM_GRAPHICS`).
+    RelativeColorimetric,
+
+    /// Maintains contrast (`LCS_GM_IMAGES`).
+    Perceptual,
+
+    /// Maintains the white point (`LCS_GM_ABS_COLORIMETRIC`).
+    AbsoluteColorimetric,
+
+    /// Unknown rendering intent.
+    Unknown(u32),
+}
+
+impl RenderingIntent {
+    const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::Saturation,
+            2 => Self::RelativeColorimetric,
+            4 => Self::Perceptual,
+            8 => Self::AbsoluteColorimetric,
+            _ => Self::Unknown(value),
+        }
+    }
+}
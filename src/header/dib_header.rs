@@ -3,15 +3,18 @@
 use embedded_graphics::prelude::*;
 
 use crate::{
-    header::CompressionMethod,
+    header::{ColorSpaceInfo, CompressionMethod},
     parser::{le_i32, le_u16, le_u32, take_slice},
     try_const, Bpp, ChannelMasks, ParseError, RowOrder,
 };
 
+const DIB_CORE_HEADER_SIZE: u32 = 12;
 const DIB_INFO_HEADER_SIZE: u32 = 40;
+const DIB_V2_HEADER_SIZE: u32 = 52;
 const DIB_V3_HEADER_SIZE: u32 = 56;
 const DIB_V4_HEADER_SIZE: u32 = 108;
 const DIB_V5_HEADER_SIZE: u32 = 124;
+const DIB_OS2_V2_HEADER_SIZE: u32 = 64;
 
 /// Device Independent Bitmap (DIB) header.
 #[derive(Debug)]
@@ -23,6 +26,11 @@ pub struct DibHeader {
     pub channel_masks: Option<ChannelMasks>,
     pub row_order: RowOrder,
     pub color_table_num_entries: u32,
+    pub color_table_entry_len: usize,
+    pub pixels_per_meter: Size,
+    pub color_planes: u16,
+    pub colors_important: u32,
+    pub color_space: Option<ColorSpaceInfo>,
 }
 
 impl DibHeader {
@@ -34,11 +42,14 @@ impl DibHeader {
         let Some(data_length) = dib_header_length.checked_sub(4) else {
             return Err(ParseError::UnsupportedHeaderLength(dib_header_length));
         };
-        let (input, dib_header_data) = try_const!(take_slice(input, data_length as usize));
+        let (mut input, dib_header_data) = try_const!(take_slice(input, data_length as usize));
 
         // Add 4 back on so the constants remain the correct size relative to the BMP
         // documentation/specs.
         let header_type = match dib_header_length {
+            DIB_CORE_HEADER_SIZE => return Self::parse_core(input, dib_header_data),
+            DIB_OS2_V2_HEADER_SIZE => HeaderType::Os2V2,
+            DIB_V2_HEADER_SIZE => HeaderType::V2,
             DIB_V3_HEADER_SIZE => HeaderType::V3,
             DIB_V4_HEADER_SIZE => HeaderType::V4,
             DIB_V5_HEADER_SIZE => HeaderType::V5,
@@ -49,8 +60,8 @@ impl DibHeader {
         // Fields common to all DIB variants
         let (dib_header_data, image_width) = try_const!(le_i32(dib_header_data));
         let (dib_header_data, image_height) = try_const!(le_i32(dib_header_data));
-        let (dib_header_data, _color_planes) = try_const!(le_u16(dib_header_data));
-        let (dib_header_data, bpp) = try_const!(Bpp::parse(dib_header_data));
+        let (dib_header_data, color_planes) = try_const!(le_u16(dib_header_data));
+        let (dib_header_data, bpp) = try_const!(le_u16(dib_header_data));
 
         // Extra fields defined by DIB variants
         // Variants are described in
@@ -58,31 +69,78 @@ impl DibHeader {
         // and <https://docs.microsoft.com/en-us/windows/win32/gdi/bitmap-header-types>
         let (dib_header_data, compression_method) =
             try_const!(CompressionMethod::parse(dib_header_data));
+
+        let bpp = if compression_method.is_embedded_image() {
+            try_const!(Bpp::new_embedded(bpp))
+        } else {
+            try_const!(Bpp::new(bpp))
+        };
+
+        // OS/2 2.x headers use the values 3 and 4 for Huffman 1D and RLE24 compression, which
+        // aren't supported.
+        if matches!(header_type, HeaderType::Os2V2) && compression_method.value() >= 3 {
+            return Err(ParseError::UnsupportedCompressionMethod(
+                compression_method.value(),
+            ));
+        }
         let (dib_header_data, image_data_len) = try_const!(le_u32(dib_header_data));
-        let (dib_header_data, _pels_per_meter_x) = try_const!(le_u32(dib_header_data));
-        let (dib_header_data, _pels_per_meter_y) = try_const!(le_u32(dib_header_data));
+        let (dib_header_data, pels_per_meter_x) = try_const!(le_u32(dib_header_data));
+        let (dib_header_data, pels_per_meter_y) = try_const!(le_u32(dib_header_data));
         let (dib_header_data, colors_used) = try_const!(le_u32(dib_header_data));
-        let (dib_header_data, _colors_important) = try_const!(le_u32(dib_header_data));
-
-        let (_dib_header_data, channel_masks) = if header_type.is_at_least(HeaderType::V3)
-            && matches!(compression_method, CompressionMethod::Bitfields)
-        {
-            let (dib_header_data, mask_red) = try_const!(le_u32(dib_header_data));
-            let (dib_header_data, mask_green) = try_const!(le_u32(dib_header_data));
-            let (dib_header_data, mask_blue) = try_const!(le_u32(dib_header_data));
-            let (dib_header_data, mask_alpha) = try_const!(le_u32(dib_header_data));
+        let (dib_header_data, colors_important) = try_const!(le_u32(dib_header_data));
+
+        let channel_masks = if matches!(
+            compression_method,
+            CompressionMethod::Bitfields | CompressionMethod::AlphaBitfields
+        ) {
+            // V2 headers contain the RGB masks and V3 and later headers also contain the alpha
+            // mask. Masks which aren't part of the header are stored directly after the header.
+            let header_masks = if header_type.is_at_least(HeaderType::V3) {
+                4
+            } else if header_type.is_at_least(HeaderType::V2) {
+                3
+            } else {
+                0
+            };
+            let num_masks = if matches!(compression_method, CompressionMethod::AlphaBitfields) {
+                4
+            } else {
+                3
+            };
+
+            let mut dib_header_data = dib_header_data;
+            let mut masks = [0; 4];
+            let mut i = 0;
+            while i < num_masks || i < header_masks {
+                if i < header_masks {
+                    (dib_header_data, masks[i]) = try_const!(le_u32(dib_header_data));
+                } else {
+                    (input, masks[i]) = try_const!(le_u32(input));
+                }
+                i += 1;
+            }
+
+            Some(ChannelMasks {
+                red: masks[0],
+                green: masks[1],
+                blue: masks[2],
+                alpha: masks[3],
+            })
+        } else {
+            None
+        };
 
-            (
+        let color_space = if header_type.is_at_least(HeaderType::V4) {
+            // V4 and later headers always contain all four channel masks, even if they aren't
+            // used by the compression method.
+            let (dib_header_data, _masks) = try_const!(take_slice(dib_header_data, 16));
+            let color_space = try_const!(ColorSpaceInfo::parse(
                 dib_header_data,
-                Some(ChannelMasks {
-                    red: mask_red,
-                    green: mask_green,
-                    blue: mask_blue,
-                    alpha: mask_alpha,
-                }),
-            )
+                header_type.is_at_least(HeaderType::V5)
+            ));
+            Some(color_space)
         } else {
-            (dib_header_data, None)
+            None
         };
 
         let color_table_num_entries = if colors_used == 0 && bpp.bits() < 16 {
@@ -111,6 +169,55 @@ impl DibHeader {
                 compression: compression_method,
                 row_order,
                 color_table_num_entries,
+                color_table_entry_len: 4,
+                pixels_per_meter: Size::new(pels_per_meter_x, pels_per_meter_y),
+                color_planes,
+                colors_important,
+                color_space,
+            },
+        ))
+    }
+
+    /// Parses the remaining fields of an OS/2 1.x `BITMAPCOREHEADER`.
+    ///
+    /// Core headers use 16 bit image dimensions, are always stored bottom-up and don't support
+    /// compression. The color table entries are stored as 3 byte `RGBTRIPLE`s.
+    const fn parse_core<'a>(
+        input: &'a [u8],
+        dib_header_data: &[u8],
+    ) -> Result<(&'a [u8], Self), ParseError> {
+        let (dib_header_data, image_width) = try_const!(le_u16(dib_header_data));
+        let (dib_header_data, image_height) = try_const!(le_u16(dib_header_data));
+        let (dib_header_data, color_planes) = try_const!(le_u16(dib_header_data));
+        let (_dib_header_data, bpp) = try_const!(Bpp::parse(dib_header_data));
+
+        if image_width == 0 || image_height == 0 {
+            return Err(ParseError::InvalidImageDimensions);
+        }
+
+        // Core headers only support 1, 4, 8 and 24 bpp images.
+        let color_table_num_entries = match bpp {
+            Bpp::Bits1 | Bpp::Bits4 | Bpp::Bits8 => 1 << bpp.bits(),
+            Bpp::Bits24 => 0,
+            _ => return Err(ParseError::UnsupportedBpp(bpp.bits())),
+        };
+
+        Ok((
+            input,
+            Self {
+                image_size: Size::new(image_width as u32, image_height as u32),
+                image_data_len: 0,
+                bpp,
+                channel_masks: None,
+                compression: CompressionMethod::Rgb,
+                row_order: RowOrder::BottomUp,
+                color_table_num_entries,
+                color_table_entry_len: 3,
+                // Core headers don't contain a resolution.
+                pixels_per_meter: Size::zero(),
+                color_planes,
+                colors_important: 0,
+                color_space: None,
             },
         ))
     }
@@ -120,6 +227,8 @@ impl DibHeader {
 #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
 pub enum HeaderType {
     Info,
+    Os2V2,
+    V2,
     V3,
     V4,
     V5,
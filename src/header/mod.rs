@@ -11,16 +11,23 @@ use crate::{
     try_const, ParseError,
 };
 
+mod color_space;
 mod dib_header;
 
+pub use color_space::{CieXyz, ColorSpace, ColorSpaceInfo, IccProfile, RenderingIntent};
 use dib_header::DibHeader;
 
+/// Length of the `BITMAPFILEHEADER` in bytes.
+pub(crate) const FILE_HEADER_LEN: usize = 14;
+
 /// Bits per pixel.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[non_exhaustive]
 pub enum Bpp {
     /// 1 bit per pixel.
     Bits1,
+    /// 2 bits per pixel.
+    Bits2,
     /// 4 bit per pixel.
     Bits4,
     /// 8 bits per pixel.
@@ -31,17 +38,24 @@ pub enum Bpp {
     Bits24,
     /// 32 bits per pixel.
     Bits32,
+    /// 64 bits per pixel.
+    ///
+    /// The color channels are stored in linear scRGB with 16 bits per channel, see
+    /// [`RawBmp::pixel_wide`](crate::RawBmp::pixel_wide) for more information.
+    Bits64,
 }
 
 impl Bpp {
     const fn new(value: u16) -> Result<Self, ParseError> {
         Ok(match value {
             1 => Self::Bits1,
+            2 => Self::Bits2,
             4 => Self::Bits4,
             8 => Self::Bits8,
             16 => Self::Bits16,
             24 => Self::Bits24,
             32 => Self::Bits32,
+            64 => Self::Bits64,
             _ => return Err(ParseError::UnsupportedBpp(value)),
         })
     }
@@ -51,35 +65,43 @@ impl Bpp {
         Ok((input, try_const!(Self::new(value))))
     }
 
+    /// Creates a bit depth for an image with an embedded JPEG or PNG image.
+    ///
+    /// The bit depth of embedded images is optional and `0` is interpreted as 24 bits per pixel.
+    const fn new_embedded(value: u16) -> Result<Self, ParseError> {
+        if value == 0 {
+            Ok(Self::Bits24)
+        } else {
+            Self::new(value)
+        }
+    }
+
     /// Returns the number of bits.
     pub const fn bits(self) -> u16 {
         match self {
             Self::Bits1 => 1,
+            Self::Bits2 => 2,
             Self::Bits4 => 4,
             Self::Bits8 => 8,
             Self::Bits16 => 16,
             Self::Bits24 => 24,
             Self::Bits32 => 32,
+            Self::Bits64 => 64,
         }
     }
 }
 
 /// Image row order.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 #[non_exhaustive]
 pub enum RowOrder {
     /// Bottom-up (standard)
+    #[default]
     BottomUp,
     /// Top-down
     TopDown,
 }
 
-impl Default for RowOrder {
-    fn default() -> Self {
-        Self::BottomUp
-    }
-}
-
 /// BMP header information.
 ///
 /// The header can be accessed by using [`RawBmp::header`](crate::RawBmp::header).
@@ -108,9 +130,30 @@ pub struct Header {
 
     /// The compression method
     pub compression_method: CompressionMethod,
+
+    /// Horizontal and vertical resolution in pixels per meter.
+    ///
+    /// The resolution is set to `0` if it isn't specified. See [`dpi`](Self::dpi) and
+    /// [`physical_size_mm`](Self::physical_size_mm) to convert the resolution into other units.
+    pub pixels_per_meter: Size,
+
+    /// Number of color planes, which is `1` in all valid files.
+    pub color_planes: u16,
+
+    /// Number of important colors in the color table.
+    ///
+    /// `0` means that all colors are important.
+    pub colors_important: u32,
+
+    /// Color space information.
+    ///
+    /// Color space information is only available for images with a `BITMAPV4HEADER` or
+    /// `BITMAPV5HEADER` header.
+    pub color_space: Option<ColorSpaceInfo>,
 }
 
 impl Header {
+    #[allow(clippy::type_complexity)]
     pub(crate) const fn parse(
         input: &[u8],
     ) -> Result<(&[u8], (Header, Option<ColorTable<'_>>)), ParseError> {
@@ -126,16 +169,72 @@ impl Header {
         let (input, _reserved_2) = try_const!(le_u16(input));
         let (input, image_data_start) = try_const!(le_u32(input));
 
-        // DIB header
+        let (input, (header, color_table)) = try_const!(Self::parse_dib_header(input));
+
+        Ok((
+            input,
+            (
+                Header {
+                    file_size,
+                    image_data_start: image_data_start as usize,
+                    ..header
+                },
+                color_table,
+            ),
+        ))
+    }
+
+    /// Parses a DIB without a file header.
+    ///
+    /// The image data is expected to start directly after the DIB header, the channel masks and
+    /// the color table. `file_size` is set to the length of the input.
+    #[allow(clippy::type_complexity)]
+    pub(crate) const fn parse_dib(
+        bytes: &[u8],
+    ) -> Result<(&[u8], (Header, Option<ColorTable<'_>>)), ParseError> {
+        let (input, (header, color_table)) = try_const!(Self::parse_dib_header(bytes));
+
+        let file_size = if bytes.len() > u32::MAX as usize {
+            u32::MAX
+        } else {
+            bytes.len() as u32
+        };
+
+        Ok((
+            input,
+            (
+                Header {
+                    file_size,
+                    image_data_start: bytes.len() - input.len(),
+                    ..header
+                },
+                color_table,
+            ),
+        ))
+    }
+
+    /// Parses the DIB header and the color table.
+    ///
+    /// The `file_size` and `image_data_start` fields of the returned header are set to `0`.
+    #[allow(clippy::type_complexity)]
+    const fn parse_dib_header(
+        input: &[u8],
+    ) -> Result<(&[u8], (Header, Option<ColorTable<'_>>)), ParseError> {
         let (input, dib_header) = try_const!(DibHeader::parse(input));
 
         let (input, color_table) = if dib_header.color_table_num_entries > 0 {
-            // Each color table entry is 4 bytes long
+            // Color table entries are 3 bytes long for core headers and 4 bytes long otherwise
             let (input, table) = try_const!(take_slice(
                 input,
-                dib_header.color_table_num_entries as usize * 4
+                dib_header.color_table_num_entries as usize * dib_header.color_table_entry_len
             ));
-            (input, Some(ColorTable::new(table)))
+            (
+                input,
+                Some(ColorTable::with_entry_len(
+                    table,
+                    dib_header.color_table_entry_len,
+                )),
+            )
         } else {
             (input, None)
         };
@@ -144,27 +243,78 @@ impl Header {
             input,
             (
                 Header {
-                    file_size,
-                    image_data_start: image_data_start as usize,
+                    file_size: 0,
+                    image_data_start: 0,
                     image_size: dib_header.image_size,
                     image_data_len: dib_header.image_data_len,
                     bpp: dib_header.bpp,
                     channel_masks: dib_header.channel_masks,
                     row_order: dib_header.row_order,
                     compression_method: dib_header.compression,
+                    pixels_per_meter: dib_header.pixels_per_meter,
+                    color_planes: dib_header.color_planes,
+                    colors_important: dib_header.colors_important,
+                    color_space: dib_header.color_space,
                 },
                 color_table,
             ),
         ))
     }
 
+    /// Returns the horizontal and vertical resolution in dots per inch.
+    ///
+    /// The resolution is rounded to the nearest integer. `None` is returned if the resolution
+    /// isn't specified in the header.
+    pub const fn dpi(&self) -> Option<Size> {
+        let Size { width, height } = self.pixels_per_meter;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        Some(Size::new(
+            pixels_per_meter_to_dpi(width),
+            pixels_per_meter_to_dpi(height),
+        ))
+    }
+
+    /// Returns the physical width and height of the image in millimeters.
+    ///
+    /// `None` is returned if the resolution isn't specified in the header.
+    pub fn physical_size_mm(&self) -> Option<(f32, f32)> {
+        let Size { width, height } = self.pixels_per_meter;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        Some((
+            self.image_size.width as f32 * 1000.0 / width as f32,
+            self.image_size.height as f32 * 1000.0 / height as f32,
+        ))
+    }
+
     /// Returns the row length in bytes.
     ///
     /// Each row in a BMP file is a multiple of 4 bytes long.
     pub(crate) const fn bytes_per_row(&self) -> usize {
         let bits_per_row = self.image_size.width as usize * self.bpp.bits() as usize;
 
-        (bits_per_row + 31) / 32 * (32 / 8)
+        bits_per_row.div_ceil(32) * (32 / 8)
+    }
+}
+
+/// Converts a resolution in pixels per meter into dots per inch.
+const fn pixels_per_meter_to_dpi(pixels_per_meter: u32) -> u32 {
+    // 1 inch = 0.0254 m
+    ((pixels_per_meter as u64 * 254 + 5000) / 10000) as u32
+}
+
+/// Converts a resolution in dots per inch into pixels per meter.
+pub(crate) const fn dpi_to_pixels_per_meter(dpi: u32) -> u32 {
+    let pixels_per_meter = (dpi as u64 * 10000 + 127) / 254;
+    if pixels_per_meter > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        pixels_per_meter as u32
     }
 }
 
@@ -191,6 +341,7 @@ impl ChannelMasks {
     };
 
     /// Rgb565 color masks.
+    #[allow(clippy::unusual_byte_groupings)]
     pub const RGB565: Self = Self {
         red: 0b11111_000000_00000,
         green: 0b00000_111111_00000,
@@ -205,6 +356,14 @@ impl ChannelMasks {
         blue: 0x0000FF,
         alpha: 0,
     };
+
+    /// Argb8888 color masks.
+    pub const ARGB8888: Self = Self {
+        red: 0x00FF0000,
+        green: 0x0000FF00,
+        blue: 0x000000FF,
+        alpha: 0xFF000000,
+    };
 }
 
 /// Describes how the BMP file is compressed.
@@ -222,6 +381,19 @@ pub enum CompressionMethod {
     /// with 4 bits per pixel. The compression uses a 2-byte format consisting
     /// of a count byte followed by two word-length color indexes.
     Rle4,
+    /// The bitmap is in uncompressed RGB, using color masks including an alpha channel mask
+    /// (`BI_ALPHABITFIELDS`). This compression method is used by Windows CE.
+    AlphaBitfields,
+    /// The image data contains an embedded JPEG image (`BI_JPEG`).
+    ///
+    /// The embedded image can be accessed by using
+    /// [`RawBmp::embedded_image`](crate::RawBmp::embedded_image).
+    Jpeg,
+    /// The image data contains an embedded PNG image (`BI_PNG`).
+    ///
+    /// The embedded image can be accessed by using
+    /// [`RawBmp::embedded_image`](crate::RawBmp::embedded_image).
+    Png,
 }
 
 impl CompressionMethod {
@@ -231,6 +403,9 @@ impl CompressionMethod {
             1 => Self::Rle8,
             2 => Self::Rle4,
             3 => Self::Bitfields,
+            4 => Self::Jpeg,
+            5 => Self::Png,
+            6 => Self::AlphaBitfields,
             _ => return Err(ParseError::UnsupportedCompressionMethod(value)),
         })
     }
@@ -239,4 +414,22 @@ impl CompressionMethod {
         let (input, value) = try_const!(le_u32(input));
         Ok((input, try_const!(Self::new(value))))
     }
+
+    /// Returns `true` if the image data contains an embedded JPEG or PNG image.
+    pub(crate) const fn is_embedded_image(self) -> bool {
+        matches!(self, Self::Jpeg | Self::Png)
+    }
+
+    /// Returns the value which is used to store the compression method in a BMP file.
+    pub(crate) const fn value(self) -> u32 {
+        match self {
+            Self::Rgb => 0,
+            Self::Rle8 => 1,
+            Self::Rle4 => 2,
+            Self::Bitfields => 3,
+            Self::Jpeg => 4,
+            Self::Png => 5,
+            Self::AlphaBitfields => 6,
+        }
+    }
 }
@@ -0,0 +1,480 @@
+//! ICO and CUR files.
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{
+    image::GetPixel,
+    pixelcolor::{Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{
+    header::Header,
+    parser::{le_u16, le_u32, take_slice},
+    raw_bmp::ColorType,
+    spans::fill_area,
+    try_const, Bmp, Bpp, CompressionMethod, ParseError, RawBmp, RowOrder,
+};
+
+/// Length of an `ICONDIRENTRY`.
+const ICON_DIR_ENTRY_SIZE: usize = 16;
+
+/// PNG file signature.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Compression method value for PNG compressed images (`BI_PNG`).
+const BI_PNG: u32 = 5;
+
+/// ICO or CUR file type.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum IcoType {
+    /// Icon (`.ico`) file.
+    Icon,
+    /// Cursor (`.cur`) file.
+    Cursor,
+}
+
+/// ICO or CUR file.
+///
+/// Icon and cursor files contain one or more images, which usually show the same icon in different
+/// sizes and bit depths. The entries can be accessed by using [`entries`](Self::entries) or the
+/// entry which fits a target size best can be selected by using [`best_match`](Self::best_match).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb888, prelude::*};
+/// use tinybmp::Ico;
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb888> = MockDisplay::default();
+///
+/// let ico = Ico::from_slice(include_bytes!("../tests/icon.ico")).unwrap();
+///
+/// let entry = ico.best_match(Size::new(16, 16)).unwrap();
+/// assert_eq!(entry.size(), Size::new(16, 16));
+///
+/// // Transparent pixels, which are marked in the AND mask, aren't drawn.
+/// let image = entry.image::<Rgb888>().unwrap();
+/// Image::new(&image, Point::zero()).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Ico<'a> {
+    data: &'a [u8],
+    ico_type: IcoType,
+    entries: &'a [u8],
+}
+
+impl<'a> Ico<'a> {
+    /// Creates an ICO or CUR file object from a byte slice.
+    ///
+    /// Returns [`ParseError::InvalidFileSignature`] with the value of the resource type field if the
+    /// file doesn't start with a valid `ICONDIR` header. The image data of all entries must be
+    /// contained in `bytes`, but the images themselves are only parsed when they are accessed.
+    pub const fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        let (input, reserved) = try_const!(le_u16(bytes));
+        let (input, ico_type_value) = try_const!(le_u16(input));
+        let (input, count) = try_const!(le_u16(input));
+
+        let ico_type = match (reserved, ico_type_value) {
+            (0, 1) => IcoType::Icon,
+            (0, 2) => IcoType::Cursor,
+            _ => {
+                return Err(ParseError::InvalidFileSignature(
+                    ico_type_value.to_le_bytes(),
+                ))
+            }
+        };
+
+        let (_, entries) = try_const!(take_slice(input, count as usize * ICON_DIR_ENTRY_SIZE));
+
+        // Check that the image data of all entries is inside the file.
+        let mut index = 0;
+        while index < count as usize {
+            let (_, entry) = entries.split_at(index * ICON_DIR_ENTRY_SIZE);
+            let (offset, length) = try_const!(parse_data_range(entry));
+            match offset.checked_add(length) {
+                Some(end) if end <= bytes.len() => {}
+                _ => return Err(ParseError::UnexpectedEndOfFile),
+            }
+
+            index += 1;
+        }
+
+        Ok(Self {
+            data: bytes,
+            ico_type,
+            entries,
+        })
+    }
+
+    /// Returns the file type.
+    pub const fn ico_type(&self) -> IcoType {
+        self.ico_type
+    }
+
+    /// Returns the number of entries.
+    pub const fn len(&self) -> usize {
+        self.entries.len() / ICON_DIR_ENTRY_SIZE
+    }
+
+    /// Returns `true` if the file contains no entries.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an entry.
+    ///
+    /// `None` is returned if `index` is out of bounds.
+    pub fn entry(&self, index: usize) -> Option<IcoEntry<'a>> {
+        let offset = index.checked_mul(ICON_DIR_ENTRY_SIZE)?;
+        let entry = self
+            .entries
+            .get(offset..offset.checked_add(ICON_DIR_ENTRY_SIZE)?)?;
+
+        let dimension = |value: u8| if value == 0 { 256 } else { u32::from(value) };
+        let word = |offset: usize| u16::from_le_bytes([entry[offset], entry[offset + 1]]);
+
+        let hotspot = match self.ico_type {
+            IcoType::Icon => None,
+            IcoType::Cursor => Some(Point::new(i32::from(word(4)), i32::from(word(6)))),
+        };
+
+        // The data range was already checked in `from_slice`.
+        let (offset, length) = parse_data_range(entry).ok()?;
+        let data = self.data.get(offset..offset + length)?;
+
+        Some(IcoEntry {
+            size: Size::new(dimension(entry[0]), dimension(entry[1])),
+            color_count: entry[2],
+            hotspot,
+            data,
+        })
+    }
+
+    /// Returns an iterator over all entries.
+    pub fn entries(&self) -> impl Iterator<Item = IcoEntry<'a>> + '_ {
+        (0..self.len()).filter_map(|index| self.entry(index))
+    }
+
+    /// Returns the entry which fits the target size best.
+    ///
+    /// The entry whose size is closest to `size` is returned. If multiple entries have the same
+    /// size, the entry with the highest bit depth is preferred. `None` is returned if the file
+    /// contains no entries.
+    pub fn best_match(&self, size: Size) -> Option<IcoEntry<'a>> {
+        self.entries().min_by_key(|entry| {
+            let distance =
+                entry.size.width.abs_diff(size.width) + entry.size.height.abs_diff(size.height);
+
+            (distance, u16::MAX - entry.bpp())
+        })
+    }
+}
+
+/// Entry in an ICO or CUR file.
+///
+/// Each entry contains a single image, which is either stored as a DIB with an additional AND
+/// mask or as a PNG file. The image can be accessed by using [`image`](Self::image), which combines
+/// the image data and the AND mask into an image with transparent pixels, or by using
+/// [`bmp`](Self::bmp), which ignores the AND mask.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct IcoEntry<'a> {
+    size: Size,
+    color_count: u8,
+    hotspot: Option<Point>,
+    data: &'a [u8],
+}
+
+impl<'a> IcoEntry<'a> {
+    /// Returns the image size, as stored in the directory entry.
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the number of colors in the color table, as stored in the directory entry.
+    ///
+    /// The value is `0` for images without a color table or with a color table which contains 256
+    /// or more entries.
+    pub const fn color_count(&self) -> u8 {
+        self.color_count
+    }
+
+    /// Returns the cursor hotspot.
+    ///
+    /// The hotspot is only available for entries in CUR files and `None` is returned for entries
+    /// in ICO files.
+    pub const fn hotspot(&self) -> Option<Point> {
+        self.hotspot
+    }
+
+    /// Returns the image data of this entry.
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns `true` if the image is stored as a PNG file.
+    pub fn is_png(&self) -> bool {
+        self.data.starts_with(PNG_SIGNATURE)
+    }
+
+    /// Returns the number of bits per pixel.
+    ///
+    /// The bit depth is read from the image header, because the value in the directory entry is
+    /// unreliable and contains the hotspot in CUR files. `0` is returned if the image header is
+    /// invalid.
+    pub fn bpp(&self) -> u16 {
+        if self.is_png() {
+            // Bit depth and color type in the IHDR chunk.
+            let channels = match self.data.get(25) {
+                Some(0 | 3) => 1,
+                Some(4) => 2,
+                Some(2) => 3,
+                Some(6) => 4,
+                _ => 0,
+            };
+            self.data
+                .get(24)
+                .map_or(0, |&depth| u16::from(depth) * channels)
+        } else {
+            self.data
+                .get(14..16)
+                .map_or(0, |bpp| u16::from_le_bytes([bpp[0], bpp[1]]))
+        }
+    }
+
+    /// Returns the raw image without the AND mask.
+    ///
+    /// 32 bpp images without channel masks contain an alpha channel. PNG compressed images return
+    /// [`ParseError::UnsupportedCompressionMethod`].
+    pub fn raw_bmp(&self) -> Result<RawBmp<'a>, ParseError> {
+        self.parse().map(|(raw_bmp, _)| raw_bmp)
+    }
+
+    /// Returns the image without the AND mask.
+    ///
+    /// All pixels are drawn opaque. Use [`image`](Self::image) to draw the image with transparent
+    /// pixels.
+    pub fn bmp<C>(&self) -> Result<Bmp<'a, C>, ParseError>
+    where
+        C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+    {
+        self.raw_bmp().map(|raw_bmp| Bmp {
+            raw_bmp,
+            color_type: PhantomData,
+        })
+    }
+
+    /// Returns the image with transparent pixels.
+    pub fn image<C>(&self) -> Result<IcoImage<'a, C>, ParseError>
+    where
+        C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+    {
+        self.parse().map(|(raw_bmp, and_mask)| IcoImage {
+            bmp: Bmp {
+                raw_bmp,
+                color_type: PhantomData,
+            },
+            and_mask,
+        })
+    }
+
+    /// Parses the DIB and returns the image and the AND mask.
+    fn parse(&self) -> Result<(RawBmp<'a>, &'a [u8]), ParseError> {
+        if self.is_png() {
+            return Err(ParseError::UnsupportedCompressionMethod(BI_PNG));
+        }
+
+        let (_, (mut header, color_table)) = Header::parse_dib(self.data)?;
+
+        // The height in the DIB header includes the AND mask, which has the same size as the
+        // image.
+        header.image_size.height /= 2;
+        if header.image_size.height == 0 {
+            return Err(ParseError::InvalidImageDimensions);
+        }
+
+        let mut raw_bmp = RawBmp::new(self.data, 0, header, color_table)?;
+        // 32 bpp images use the fourth byte as the alpha channel. Like Windows, legacy images which
+        // store zero in all alpha values are drawn by using the AND mask instead.
+        if header.bpp == Bpp::Bits32
+            && header.compression_method == CompressionMethod::Rgb
+            && raw_bmp
+                .image_data()
+                .chunks_exact(4)
+                .any(|pixel| pixel[3] != 0)
+        {
+            raw_bmp.color_type = ColorType::Argb8888;
+        }
+
+        // The AND mask directly follows the image data. Missing masks are treated as fully opaque.
+        let mask_start = header.image_data_start + raw_bmp.image_data().len();
+        let mask_len =
+            mask_bytes_per_row(header.image_size.width) * header.image_size.height as usize;
+        let and_mask = self
+            .data
+            .get(mask_start..mask_start + mask_len)
+            .unwrap_or_default();
+
+        Ok((raw_bmp, and_mask))
+    }
+}
+
+/// Image in an ICO or CUR file with transparent pixels.
+///
+/// Pixels which are marked as transparent in the AND mask are skipped when the image is drawn and
+/// [`GetPixel::pixel`] returns `None` for these pixels. Images with an alpha channel use the alpha
+/// channel instead of the AND mask and only fully transparent pixels are skipped. 32 bpp images
+/// in which all alpha values are zero are treated as images without an alpha channel. Use
+/// [`Bmp::with_alpha`] on the image returned by [`as_bmp`](Self::as_bmp) to blend semi-transparent
+/// pixels with a background color.
+///
+/// This struct is created by [`IcoEntry::image`] and can be drawn to any [`DrawTarget`] by wrapping
+/// it in an [`Image`](embedded_graphics::image::Image).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct IcoImage<'a, C> {
+    bmp: Bmp<'a, C>,
+    and_mask: &'a [u8],
+}
+
+impl<'a, C> IcoImage<'a, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    /// Returns a reference to the image without the AND mask.
+    pub const fn as_bmp(&self) -> &Bmp<'a, C> {
+        &self.bmp
+    }
+
+    /// Returns `true` if a pixel is transparent.
+    ///
+    /// Pixels outside the image bounding box are also transparent.
+    pub fn is_transparent(&self, p: Point) -> bool {
+        let raw_bmp = self.bmp.as_raw();
+        if raw_bmp.has_alpha() {
+            return !matches!(raw_bmp.alpha(p), Some(1..));
+        }
+
+        self.is_masked(p)
+    }
+
+    /// Returns `true` if a pixel is marked as transparent in the AND mask.
+    ///
+    /// Pixels outside the image bounding box are also masked.
+    fn is_masked(&self, p: Point) -> bool {
+        let header = self.bmp.as_raw().header();
+        let size = header.image_size;
+        if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+            return true;
+        }
+
+        let row = match header.row_order {
+            RowOrder::BottomUp => size.height - 1 - p.y as u32,
+            RowOrder::TopDown => p.y as u32,
+        };
+        let offset = row as usize * mask_bytes_per_row(size.width) + p.x as usize / 8;
+
+        self.and_mask
+            .get(offset)
+            .is_some_and(|byte| byte & (0x80 >> (p.x % 8)) != 0)
+    }
+
+    /// Converts the raw value of the pixel at `p` into a color.
+    ///
+    /// Returns `None` if the pixel is transparent.
+    fn color(&self, p: Point, raw: u32) -> Option<C> {
+        let raw_bmp = self.bmp.as_raw();
+        let is_transparent = if raw_bmp.has_alpha() {
+            raw_bmp.color_type.alpha(raw) == 0
+        } else {
+            self.is_masked(p)
+        };
+
+        if is_transparent {
+            None
+        } else {
+            raw_bmp.color_type.to_color(raw, raw_bmp.color_table())
+        }
+    }
+
+    /// Draws a part of the image.
+    ///
+    /// The top left corner of `area` is drawn at the origin of `target`. `area` must be inside
+    /// the image bounding box.
+    fn draw_area<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        fill_area(self.bmp.as_raw(), target, area, |p, raw| self.color(p, raw))
+    }
+}
+
+impl<C> ImageDrawable for IcoImage<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_area(target, &self.bounding_box())
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        // Only the part of the area which overlaps the image is decoded.
+        let source = area.intersection(&self.bounding_box());
+        if source.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.draw_area(
+            &mut target.translated(source.top_left - area.top_left),
+            &source,
+        )
+    }
+}
+
+impl<C> OriginDimensions for IcoImage<'_, C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.bmp.size()
+    }
+}
+
+impl<C> GetPixel for IcoImage<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    /// Returns the color of a pixel.
+    ///
+    /// Returns `None` if `p` is outside the image bounding box or if the pixel is transparent.
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        self.color(p, self.bmp.as_raw().pixel(p)?)
+    }
+}
+
+/// Parses the image data offset and length of an `ICONDIRENTRY`.
+const fn parse_data_range(entry: &[u8]) -> Result<(usize, usize), ParseError> {
+    let (input, _) = try_const!(take_slice(entry, 8));
+    let (input, length) = try_const!(le_u32(input));
+    let (_, offset) = try_const!(le_u32(input));
+
+    Ok((offset as usize, length as usize))
+}
+
+/// Returns the row length of the AND mask in bytes.
+///
+/// The AND mask uses 1 bit per pixel and each row is a multiple of 4 bytes long.
+const fn mask_bytes_per_row(width: u32) -> usize {
+    (width as usize).div_ceil(32) * 4
+}
@@ -1,10 +1,7 @@
 use core::marker::PhantomData;
 
 use embedded_graphics::{
-    pixelcolor::{
-        raw::{RawU16, RawU24},
-        Rgb555, Rgb565, Rgb888,
-    },
+    pixelcolor::{Rgb555, Rgb565, Rgb888},
     prelude::*,
 };
 
@@ -49,14 +46,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let RawPixel { position, color } = self.raw_pixels.next()?;
 
-        let color = match self.image_color_type {
-            ColorType::Index1 | ColorType::Index4 | ColorType::Index8 => {
-                self.color_table?.get(color).unwrap_or_default().into()
-            }
-            ColorType::Rgb555 => Rgb555::from(RawU16::from_u32(color)).into(),
-            ColorType::Rgb565 => Rgb565::from(RawU16::from_u32(color)).into(),
-            ColorType::Rgb888 | ColorType::Xrgb8888 => Rgb888::from(RawU24::from_u32(color)).into(),
-        };
+        let color = self.image_color_type.to_color(color, self.color_table)?;
 
         Some(Pixel(position, color))
     }
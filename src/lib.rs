@@ -79,9 +79,9 @@
 //! # Ok::<(), core::convert::Infallible>(()) }
 //! ```
 //!
-//! Note that you currently cannot access invidual pixels when working with RLE4
-//! or RLE8 compressed indexed bitmaps. With these formats the `pixel()`
-//! function will always return `None`.
+//! Individual pixels in RLE4 or RLE8 compressed indexed bitmaps can only be accessed by decoding
+//! the image up to the accessed pixel. To speed up repeated accesses a row index can be built once
+//! by using [`RawBmp::build_rle_index`] and passed to [`Bmp::with_rle_index`].
 //!
 //! ## Accessing the raw image data
 //!
@@ -96,23 +96,37 @@
 //!
 //! ```
 //! use embedded_graphics::prelude::*;
-//! use tinybmp::{RawBmp, Bpp, Header, RawPixel, RowOrder, CompressionMethod};
+//! use tinybmp::{
+//!     Bpp, CieXyz, ColorSpace, ColorSpaceInfo, CompressionMethod, Header, RawBmp, RawPixel,
+//!     RenderingIntent, RowOrder,
+//! };
 //!
-//! let bmp = RawBmp::from_slice(include_bytes!("../tests/chessboard-8px-24bit.bmp"))
+//! let bmp = RawBmp::from_slice(include_bytes!("../tests/chessboard-8px-24bit-v5.bmp"))
 //!     .expect("Failed to parse BMP image");
 //!
 //! // Read the BMP header
 //! assert_eq!(
 //!     bmp.header(),
 //!     &Header {
-//!         file_size: 314,
-//!         image_data_start: 122,
+//!         file_size: 330,
+//!         image_data_start: 138,
 //!         bpp: Bpp::Bits24,
 //!         image_size: Size::new(8, 8),
 //!         image_data_len: 192,
 //!         channel_masks: None,
 //!         row_order: RowOrder::BottomUp,
 //!         compression_method: CompressionMethod::Rgb,
+//!         pixels_per_meter: Size::new(2835, 2835),
+//!         color_planes: 1,
+//!         colors_important: 0,
+//!         color_space: Some(ColorSpaceInfo {
+//!             color_space: ColorSpace::Srgb,
+//!             endpoints: [CieXyz::default(); 3],
+//!             gamma: [0; 3],
+//!             intent: Some(RenderingIntent::Perceptual),
+//!             profile_offset: 0,
+//!             profile_size: 0,
+//!         }),
 //!     }
 //! );
 //!
@@ -143,6 +157,8 @@
 //! [`Bmp::as_raw`]: https://docs.rs/tinybmp/latest/tinybmp/struct.Bmp.html#method.as_raw
 //! [`RawBmp`]: https://docs.rs/tinybmp/latest/tinybmp/struct.RawBmp.html
 //! [`RawBmp::pixel`]: https://docs.rs/tinybmp/latest/tinybmp/struct.RawBmp.html#method.pixel
+//! [`RawBmp::build_rle_index`]: https://docs.rs/tinybmp/latest/tinybmp/struct.RawBmp.html#method.build_rle_index
+//! [`Bmp::with_rle_index`]: https://docs.rs/tinybmp/latest/tinybmp/struct.Bmp.html#method.with_rle_index
 //! [header information]: https://docs.rs/tinybmp/latest/tinybmp/struct.RawBmp.html#method.header
 //! [color table]: https://docs.rs/tinybmp/latest/tinybmp/struct.RawBmp.html#method.color_table
 //! [`from_slice`]: https://docs.rs/tinybmp/latest/tinybmp/struct.RawBmp.html#method.from_slice
@@ -178,19 +194,36 @@ use core::marker::PhantomData;
 use embedded_graphics::{
     image::GetPixel,
     pixelcolor::{
-        raw::{RawU1, RawU16, RawU24, RawU32, RawU4, RawU8},
-        Rgb555, Rgb565, Rgb888,
+        raw::{RawU1, RawU16, RawU2, RawU24, RawU32, RawU4, RawU8},
+        Gray8, Rgb555, Rgb565, Rgb888,
     },
     prelude::*,
     primitives::Rectangle,
 };
 
+mod alpha;
+mod bitfields;
+mod color_key;
 mod color_table;
+mod encoder;
+mod framebuffer;
 mod header;
+mod ico;
 mod iter;
+mod lookup_table;
+mod palette;
 mod parser;
+#[cfg(feature = "png")]
+mod png;
 mod raw_bmp;
+mod raw_bmp_mut;
 mod raw_iter;
+mod raw_target;
+mod scrgb;
+mod spans;
+#[cfg(feature = "embedded-io")]
+mod stream;
+mod tileset;
 
 /// Alternative to the `?` operator that is usable in const contexts.
 // MSRV: Replace macro with `?` operator when it get gets supported in const contexts.
@@ -204,14 +237,35 @@ macro_rules! try_const {
 }
 pub(crate) use try_const;
 
-use raw_bmp::ColorType;
+use lookup_table::LookupTable;
+use raw_bmp::{rle_area, ColorType};
+use spans::fill_opaque_spans;
 
+pub use alpha::{AlphaBmp, AlphaMode};
+pub use color_key::{ColorKey, ColorKeyBmp};
 pub use color_table::ColorTable;
+pub use encoder::{BufferTooSmall, ByteSink, EncodeError, Encoder, RleEncoder};
+pub use framebuffer::BmpFramebuffer;
 pub use header::CompressionMethod;
-pub use header::{Bpp, ChannelMasks, Header, RowOrder};
+pub use header::{
+    Bpp, ChannelMasks, CieXyz, ColorSpace, ColorSpaceInfo, Header, IccProfile, RenderingIntent,
+    RowOrder,
+};
+pub use ico::{Ico, IcoEntry, IcoImage, IcoType};
 pub use iter::Pixels;
+pub use palette::PaletteBmp;
+#[cfg(feature = "png")]
+pub use png::{PngError, PngImage};
 pub use raw_bmp::RawBmp;
-pub use raw_iter::{DynamicRawColors, RawColors, RawPixel, RawPixels, Rle4Colors, Rle8Colors};
+pub use raw_bmp_mut::RawBmpMut;
+pub use raw_iter::{
+    DynamicRawColors, Raw64Colors, RawColors, RawPixel, RawPixels, RawRow, RawRows, Rle4Colors,
+    Rle8Colors,
+};
+pub use raw_target::{RawDrawTarget, RawFormat};
+#[cfg(feature = "embedded-io")]
+pub use stream::{StreamError, StreamingBmp};
+pub use tileset::Tileset;
 
 /// A BMP-format bitmap.
 ///
@@ -239,6 +293,18 @@ where
         })
     }
 
+    /// Creates a bitmap object from a byte slice which contains a DIB without a file header.
+    ///
+    /// See [`RawBmp::from_dib_slice`] for more information.
+    pub const fn from_dib_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        let raw_bmp = try_const!(RawBmp::from_dib_slice(bytes));
+
+        Ok(Self {
+            raw_bmp,
+            color_type: PhantomData,
+        })
+    }
+
     /// Returns an iterator over the pixels in this image.
     ///
     /// The iterator always starts at the top left corner of the image, regardless of the row order
@@ -247,26 +313,383 @@ where
         Pixels::new(self)
     }
 
+    /// Reads a row of converted pixel colors into a buffer.
+    ///
+    /// Row `y` is counted from the top of the image, regardless of the row order of the BMP file.
+    /// If `buffer` is shorter than the image width only the leftmost pixels in the row are read.
+    /// Returns the number of pixels which were written to `buffer`, which is `0` if `y` is outside
+    /// the image.
+    ///
+    /// Undefined pixels in RLE compressed images, which are skipped by delta escapes or end of
+    /// line markers, leave the corresponding entries in `buffer` unchanged. See
+    /// [`RawBmp::rows`] to access the raw row data without color conversion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::pixelcolor::Rgb565;
+    /// use tinybmp::Bmp;
+    ///
+    /// # let data = include_bytes!("../tests/logo-indexed-8bpp.bmp");
+    /// let bmp = Bmp::<Rgb565>::from_slice(data).unwrap();
+    ///
+    /// let mut row = [Rgb565::default(); 240];
+    /// for y in 0..bmp.as_raw().header().image_size.height {
+    ///     let len = bmp.read_row(y, &mut row);
+    ///     // Send `row[..len]` to the display.
+    /// #   assert_eq!(len, 240);
+    /// }
+    /// ```
+    pub fn read_row(&self, y: u32, buffer: &mut [C]) -> usize {
+        let size = self.raw_bmp.header().image_size;
+        if y >= size.height {
+            return 0;
+        }
+
+        let width = size.width.min(buffer.len() as u32);
+        let area = Rectangle::new(Point::new(0, y as i32), Size::new(width, 1));
+
+        self.draw_area(&mut RowTarget(&mut buffer[..width as usize]), &area)
+            .unwrap_or_else(|e| match e {});
+
+        width as usize
+    }
+
     /// Returns a reference to the raw BMP image.
     ///
     /// The [`RawBmp`] instance can be used to access lower level information about the BMP file.
     pub const fn as_raw(&self) -> &RawBmp<'a> {
         &self.raw_bmp
     }
-}
 
-impl<C> ImageDrawable for Bmp<'_, C>
-where
-    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
-{
-    type Color = C;
+    /// Uses a row index to speed up random pixel access in RLE compressed images.
+    ///
+    /// See [`RawBmp::build_rle_index`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::{image::GetPixel, pixelcolor::Rgb888, prelude::*};
+    /// use tinybmp::Bmp;
+    ///
+    /// # let data = include_bytes!("../tests/logo-indexed-8bpp-rle8.bmp");
+    /// let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+    ///
+    /// let mut index = [0; 320];
+    /// bmp.as_raw().build_rle_index(&mut index);
+    ///
+    /// let bmp = bmp.with_rle_index(&index);
+    /// let color = bmp.pixel(Point::new(20, 10));
+    /// ```
+    pub const fn with_rle_index(self, index: &'a [u32]) -> Self {
+        Self {
+            raw_bmp: self.raw_bmp.with_rle_index(index),
+            color_type: PhantomData,
+        }
+    }
 
-    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    /// Returns the alpha value of a pixel.
+    ///
+    /// See [`RawBmp::alpha`] for more information.
+    pub fn alpha(&self, p: Point) -> Option<u8> {
+        self.raw_bmp.alpha(p)
+    }
+
+    /// Returns a drawable which takes the alpha channel into account.
+    ///
+    /// By default [`Bmp`] ignores the alpha channel and draws all pixels opaque. The returned
+    /// [`AlphaBmp`] uses the given [`AlphaMode`] to either skip transparent pixels or to blend them
+    /// with a background color.
+    pub const fn with_alpha(self, mode: AlphaMode) -> AlphaBmp<'a, C> {
+        AlphaBmp::new(self, mode)
+    }
+
+    /// Returns a drawable which skips all pixels with the given color.
+    ///
+    /// The pixels are compared after they are converted into `C`. This is useful for images
+    /// which use a "magic" color, like magenta, to mark transparent pixels. Transparent pixels
+    /// are also returned as `None` by [`GetPixel::pixel`] of the returned [`ColorKeyBmp`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::{image::Image, pixelcolor::Rgb565, prelude::*};
+    /// use tinybmp::Bmp;
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+    ///
+    /// let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/chessboard-8px-24bit.bmp"))
+    ///     .unwrap();
+    ///
+    /// // Only draw the white squares.
+    /// let bmp = bmp.with_transparent_color(Rgb565::BLACK);
+    /// Image::new(&bmp, Point::zero()).draw(&mut display)?;
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    pub const fn with_transparent_color(self, color: C) -> ColorKeyBmp<'a, C> {
+        ColorKeyBmp::new(self, ColorKey::Color(color))
+    }
+
+    /// Returns a drawable which skips all pixels with the given color index.
+    ///
+    /// Unlike [`with_transparent_color`](Self::with_transparent_color) this only skips pixels
+    /// which use the given color table entry, even if other entries contain the same color. Images
+    /// without a color table are drawn like the original image.
+    pub const fn with_transparent_index(self, index: u8) -> ColorKeyBmp<'a, C> {
+        ColorKeyBmp::new(self, ColorKey::Index(index))
+    }
+
+    /// Draws an indexed image by mapping the color indices of the pixels to colors.
+    ///
+    /// [`draw`](ImageDrawable::draw) converts the color table entries into `C`, which can be
+    /// lossy if the target doesn't use an RGB color type. This method instead passes the color
+    /// index of each pixel to `map`, which makes it possible to choose the target colors directly.
+    /// For example, 1 bpp images can be drawn to a [`BinaryColor`] display without depending on
+    /// the brightness of the color table entries.
+    ///
+    /// The top left corner of the image is drawn at the origin of `target`. Undefined pixels in
+    /// RLE compressed images aren't drawn. Images with more than 8 bits per pixel don't contain
+    /// color indices and aren't drawn at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::{pixelcolor::{BinaryColor, Rgb888}, prelude::*};
+    /// use tinybmp::Bmp;
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # let mut display: MockDisplay<BinaryColor> = MockDisplay::default();
+    ///
+    /// let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/chessboard-8px-1bit.bmp"))
+    ///     .unwrap();
+    ///
+    /// // Draw pixels which use the second color table entry as `On`.
+    /// bmp.draw_indexed(&mut display, |index| BinaryColor::from(index == 1))?;
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    ///
+    /// [`BinaryColor`]: embedded_graphics::pixelcolor::BinaryColor
+    pub fn draw_indexed<D, F>(&self, target: &mut D, map: F) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        F: Fn(u8) -> D::Color,
+    {
+        self.draw_indexed_area(target, &self.bounding_box(), map)
+    }
+
+    /// Draws an indexed image with a palette, which replaces the color table.
+    ///
+    /// `palette` is called once for each color index before the image is drawn, which is
+    /// cheaper than [`draw_indexed`](Self::draw_indexed) if the mapping is expensive to compute.
+    /// This can be used to change the colors of indexed images at runtime, e.g. to switch between
+    /// a light and a dark theme. See [`with_palette`](Self::with_palette) to use a palette stored
+    /// in a slice.
+    ///
+    /// The top left corner of the image is drawn at the origin of `target`. Images with more than
+    /// 8 bits per pixel don't contain color indices and aren't drawn at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    /// use tinybmp::Bmp;
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+    ///
+    /// let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/chessboard-8px-1bit.bmp"))
+    ///     .unwrap();
+    ///
+    /// // Draw the image in blue and yellow.
+    /// bmp.draw_with_palette(&mut display, |index| {
+    ///     if index == 0 {
+    ///         Rgb565::BLUE
+    ///     } else {
+    ///         Rgb565::YELLOW
+    ///     }
+    /// })?;
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    pub fn draw_with_palette<D, F>(&self, target: &mut D, palette: F) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        F: Fn(u8) -> D::Color,
+    {
+        let lookup_table = LookupTable::new(self.raw_bmp.color_type, palette);
+
+        self.draw_indexed(target, |index| lookup_table.get(index))
+    }
+
+    /// Returns a drawable which uses a palette instead of the color table.
+    ///
+    /// Color indices which are out of bounds of `palette` use the color from the image's color
+    /// table. Images without color indices are drawn like the original image. See
+    /// [`draw_with_palette`](Self::draw_with_palette) to use a closure instead of a slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::{image::Image, pixelcolor::Rgb565, prelude::*};
+    /// use tinybmp::Bmp;
+    /// # use embedded_graphics::mock_display::MockDisplay;
+    /// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+    ///
+    /// let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("../tests/chessboard-8px-1bit.bmp"))
+    ///     .unwrap();
+    ///
+    /// const DARK_THEME: &[Rgb565] = &[Rgb565::WHITE, Rgb565::BLACK];
+    ///
+    /// let dark = bmp.with_palette(DARK_THEME);
+    /// Image::new(&dark, Point::zero()).draw(&mut display)?;
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    pub const fn with_palette(self, palette: &'a [C]) -> PaletteBmp<'a, C> {
+        PaletteBmp::new(self, palette)
+    }
+
+    /// Draws the image to a draw target which accepts raw pixel data.
+    ///
+    /// If the pixel format of the image matches the [`RawDrawTarget::RAW_FORMAT`] of the target
+    /// the image data is passed to [`RawDrawTarget::fill_contiguous_raw`] without color
+    /// conversion. The image data of top-down images without padding bytes at the end of each row
+    /// is transferred in a single call, all other images are transferred row by row.
+    ///
+    /// Images with other pixel formats are drawn by using the regular color conversion. The top
+    /// left corner of the image is drawn at `position`. See [`RawDrawTarget`] for an example.
+    pub fn draw_raw<D>(&self, target: &mut D, position: Point) -> Result<(), D::Error>
+    where
+        D: RawDrawTarget<Color = C>,
+    {
+        if self.raw_bmp.color_type.raw_format() != Some(D::RAW_FORMAT) {
+            return self.draw(&mut target.translated(position));
+        }
+
+        let header = self.raw_bmp.header();
+        let size = header.image_size;
+        let row_len = size.width as usize * header.bpp.bits() as usize / 8;
+
+        if header.row_order == RowOrder::TopDown && row_len == header.bytes_per_row() {
+            // The image data of some compression methods can contain additional bytes or can be
+            // shorter than the image, because its length is read from the header.
+            let data_len = row_len.saturating_mul(size.height as usize);
+            if let Some(data) = self.raw_bmp.image_data().get(..data_len) {
+                return target.fill_contiguous_raw(&Rectangle::new(position, size), data);
+            }
+        }
+
+        for row in self.raw_bmp.rows() {
+            target.fill_contiguous_raw(
+                &Rectangle::new(
+                    position + Point::new(0, row.index as i32),
+                    Size::new(size.width, 1),
+                ),
+                row.data,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Draws the image to a grayscale draw target.
+    ///
+    /// Indexed images with a grayscale color table, see [`ColorTable::is_grayscale`], are drawn
+    /// by using the gray levels from the color table directly. All other images are converted to
+    /// [`Gray8`] in the same way as a `Bmp<Gray8>`.
+    pub fn draw_gray8<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Gray8>,
+    {
+        match self.raw_bmp.color_table() {
+            Some(color_table) if color_table.is_grayscale() && self.is_indexed() => {
+                let levels = LookupTable::new(self.raw_bmp.color_type, |index| {
+                    color_table
+                        .get_gray8(u32::from(index))
+                        .unwrap_or(Gray8::BLACK)
+                });
+
+                self.draw_indexed(target, |index| levels.get(index))
+            }
+            _ => Bmp::<Gray8> {
+                raw_bmp: self.raw_bmp,
+                color_type: PhantomData,
+            }
+            .draw(target),
+        }
+    }
+
+    /// Returns `true` if the image contains color indices.
+    fn is_indexed(&self) -> bool {
+        matches!(
+            self.raw_bmp.color_type,
+            ColorType::Index1 | ColorType::Index2 | ColorType::Index4 | ColorType::Index8
+        )
+    }
+
+    /// Draws a part of an indexed image by mapping the color indices to colors.
+    ///
+    /// The top left corner of `area` is drawn at the origin of `target`. `area` must be inside the
+    /// image bounding box.
+    fn draw_indexed_area<D, F>(
+        &self,
+        target: &mut D,
+        area: &Rectangle,
+        map: F,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        F: Fn(u8) -> D::Color,
+    {
+        let dest = Rectangle::new(Point::zero(), area.size);
+        let compression_method = self.raw_bmp.header().compression_method;
+
+        match self.raw_bmp.color_type {
+            ColorType::Index1 => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU1>::with_area(&self.raw_bmp, area)
+                    .map(|index| map(index.into_inner())),
+            ),
+            ColorType::Index2 => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU2>::with_area(&self.raw_bmp, area)
+                    .map(|index| map(index.into_inner())),
+            ),
+            ColorType::Index4 if compression_method == CompressionMethod::Rle4 => {
+                let colors = rle_area::<Rle4Colors<'_>>(&self.raw_bmp, area)
+                    .map(|index| index.map(|index| map(index.into_inner())));
+                fill_opaque_spans(target, dest.size, RowOrder::BottomUp, colors)
+            }
+            ColorType::Index4 => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU4>::with_area(&self.raw_bmp, area)
+                    .map(|index| map(index.into_inner())),
+            ),
+            ColorType::Index8 if compression_method == CompressionMethod::Rle8 => {
+                let colors = rle_area::<Rle8Colors<'_>>(&self.raw_bmp, area)
+                    .map(|index| index.map(|index| map(index.into_inner())));
+                fill_opaque_spans(target, dest.size, RowOrder::BottomUp, colors)
+            }
+            ColorType::Index8 => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU8>::with_area(&self.raw_bmp, area)
+                    .map(|index| map(index.into_inner())),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Draws a part of the image.
+    ///
+    /// The top left corner of `area` is drawn at the origin of `target`. Only the rows and columns
+    /// inside the area are decoded, which makes drawing small parts of large images more efficient.
+    /// `area` must be inside the image bounding box.
+    fn draw_area<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
     where
         D: DrawTarget<Color = C>,
     {
-        let area = self.bounding_box();
-        let slice_size = Size::new(area.size.width, 1);
+        let dest = Rectangle::new(Point::zero(), area.size);
+
+        // Embedded JPEG and PNG images aren't decoded.
+        if self.raw_bmp.header().compression_method.is_embedded_image() {
+            return Ok(());
+        }
 
         match self.raw_bmp.color_type {
             ColorType::Index1 => {
@@ -277,112 +700,155 @@ where
                         color_table.get(1).map(Into::into).unwrap_or(fallback_color),
                     ];
 
-                    let colors = RawColors::<RawU1>::new(&self.raw_bmp).map(|index| {
+                    let colors = RawColors::<RawU1>::with_area(&self.raw_bmp, area).map(|index| {
                         color_table
                             .get(usize::from(index.into_inner()))
                             .copied()
                             .unwrap_or(fallback_color)
                     });
-                    target.fill_contiguous(&area, colors)
+                    target.fill_contiguous(&dest, colors)
+                } else {
+                    Ok(())
+                }
+            }
+            ColorType::Index2 => {
+                if let Some(color_table) = self.raw_bmp.color_table() {
+                    let fallback_color = C::from(Rgb888::BLACK);
+                    let color_table: [C; 4] = core::array::from_fn(|index| {
+                        color_table
+                            .get(index as u32)
+                            .map(Into::into)
+                            .unwrap_or(fallback_color)
+                    });
+
+                    let colors = RawColors::<RawU2>::with_area(&self.raw_bmp, area)
+                        .map(|index| color_table[usize::from(index.into_inner())]);
+                    target.fill_contiguous(&dest, colors)
                 } else {
                     Ok(())
                 }
             }
             ColorType::Index4 => {
-                let header = self.raw_bmp.header();
-                let fallback_color = C::from(Rgb888::BLACK);
                 if let Some(color_table) = self.raw_bmp.color_table() {
-                    if header.compression_method == CompressionMethod::Rle4 {
-                        let mut colors = Rle4Colors::new(&self.raw_bmp);
-                        let map_color = |color: RawU4| {
-                            color_table
-                                .get(color.into_inner() as u32)
-                                .map(Into::into)
-                                .unwrap_or(fallback_color)
-                        };
-                        // RLE produces pixels in bottom-up order, so we draw them line by line rather than the entire bitmap at once.
-                        for y in (0..area.size.height).rev() {
-                            colors.start_row();
-
-                            let row = Rectangle::new(Point::new(0, y as i32), slice_size);
-                            let colors = colors.by_ref().map(map_color);
-                            target.fill_contiguous(&row, colors.take(area.size.width as usize))?;
-                        }
-                        Ok(())
+                    let fallback_color = C::from(Rgb888::BLACK);
+                    let color_table: [C; 16] = core::array::from_fn(|index| {
+                        color_table
+                            .get(index as u32)
+                            .map(Into::into)
+                            .unwrap_or(fallback_color)
+                    });
+                    let map_color = |index: RawU4| color_table[usize::from(index.into_inner())];
+
+                    if self.raw_bmp.header().compression_method == CompressionMethod::Rle4 {
+                        // RLE produces pixels in bottom-up order. Undefined pixels, which are
+                        // skipped by delta escapes or end of line markers, aren't drawn.
+                        let colors = rle_area::<Rle4Colors<'_>>(&self.raw_bmp, area)
+                            .map(|index| index.map(map_color));
+                        fill_opaque_spans(target, dest.size, RowOrder::BottomUp, colors)
                     } else {
                         // If we didn't detect a supported compression method, just intepret it as raw indexed nibbles.
-                        let colors = RawColors::<RawU4>::new(&self.raw_bmp).map(|index| {
-                            color_table
-                                .get(u32::from(index.into_inner()))
-                                .map(Into::into)
-                                .unwrap_or(fallback_color)
-                        });
-                        target.fill_contiguous(&area, colors)
+                        let colors =
+                            RawColors::<RawU4>::with_area(&self.raw_bmp, area).map(map_color);
+                        target.fill_contiguous(&dest, colors)
                     }
                 } else {
                     Ok(())
                 }
             }
             ColorType::Index8 => {
-                let header = self.raw_bmp.header();
-                let fallback_color = C::from(Rgb888::BLACK);
                 if let Some(color_table) = self.raw_bmp.color_table() {
-                    if header.compression_method == CompressionMethod::Rle8 {
-                        let mut colors = Rle8Colors::new(&self.raw_bmp);
-                        let map_color = |color: RawU8| {
-                            color_table
-                                .get(color.into_inner() as u32)
-                                .map(Into::into)
-                                .unwrap_or(fallback_color)
-                        };
-                        // RLE produces pixels in bottom-up order, so we draw them line by line rather than the entire bitmap at once.
-                        for y in (0..area.size.height).rev() {
-                            colors.start_row();
-
-                            let row = Rectangle::new(Point::new(0, y as i32), slice_size);
-                            let colors = colors.by_ref().map(map_color);
-                            target.fill_contiguous(&row, colors.take(area.size.width as usize))?;
-                        }
-                        Ok(())
+                    let fallback_color = C::from(Rgb888::BLACK);
+
+                    let lookup_table = LookupTable::new(ColorType::Index8, |index| {
+                        color_table
+                            .get(u32::from(index))
+                            .map(Into::into)
+                            .unwrap_or(fallback_color)
+                    });
+                    let map_color = |index: RawU8| lookup_table.get(index.into_inner());
+
+                    if self.raw_bmp.header().compression_method == CompressionMethod::Rle8 {
+                        // RLE produces pixels in bottom-up order. Undefined pixels, which are
+                        // skipped by delta escapes or end of line markers, aren't drawn.
+                        let colors = rle_area::<Rle8Colors<'_>>(&self.raw_bmp, area)
+                            .map(|index| index.map(map_color));
+                        fill_opaque_spans(target, dest.size, RowOrder::BottomUp, colors)
                     } else {
                         // If we didn't detect a supported compression method, just intepret it as raw indexed bytes.
-                        let colors = RawColors::<RawU8>::new(&self.raw_bmp).map(|index| {
-                            color_table
-                                .get(u32::from(index.into_inner()))
-                                .map(Into::into)
-                                .unwrap_or(fallback_color)
-                        });
-                        target.fill_contiguous(&area, colors)
+                        let colors =
+                            RawColors::<RawU8>::with_area(&self.raw_bmp, area).map(map_color);
+                        target.fill_contiguous(&dest, colors)
                     }
                 } else {
                     Ok(())
                 }
             }
             ColorType::Rgb555 => target.fill_contiguous(
-                &area,
-                RawColors::<RawU16>::new(&self.raw_bmp).map(|raw| Rgb555::from(raw).into()),
+                &dest,
+                RawColors::<RawU16>::with_area(&self.raw_bmp, area)
+                    .map(|raw| Rgb555::from(raw).into()),
             ),
             ColorType::Rgb565 => target.fill_contiguous(
-                &area,
-                RawColors::<RawU16>::new(&self.raw_bmp).map(|raw| Rgb565::from(raw).into()),
+                &dest,
+                RawColors::<RawU16>::with_area(&self.raw_bmp, area)
+                    .map(|raw| Rgb565::from(raw).into()),
             ),
             ColorType::Rgb888 => target.fill_contiguous(
-                &area,
-                RawColors::<RawU24>::new(&self.raw_bmp).map(|raw| Rgb888::from(raw).into()),
+                &dest,
+                RawColors::<RawU24>::with_area(&self.raw_bmp, area)
+                    .map(|raw| Rgb888::from(raw).into()),
             ),
-            ColorType::Xrgb8888 => target.fill_contiguous(
-                &area,
-                RawColors::<RawU32>::new(&self.raw_bmp)
+            ColorType::Xrgb8888 | ColorType::Argb8888 => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU32>::with_area(&self.raw_bmp, area)
                     .map(|raw| Rgb888::from(RawU24::new(raw.into_inner())).into()),
             ),
+            ColorType::Bitfields16(bitfields) => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU16>::with_area(&self.raw_bmp, area)
+                    .map(|raw| bitfields.to_rgb888(u32::from(raw.into_inner())).into()),
+            ),
+            ColorType::Bitfields32(bitfields) => target.fill_contiguous(
+                &dest,
+                RawColors::<RawU32>::with_area(&self.raw_bmp, area)
+                    .map(|raw| bitfields.to_rgb888(raw.into_inner()).into()),
+            ),
+            ColorType::Rgb64 => target.fill_contiguous(
+                &dest,
+                Raw64Colors::with_area(&self.raw_bmp, area)
+                    .map(|raw| Rgb888::from(RawU24::new(scrgb::to_argb8888(raw))).into()),
+            ),
         }
     }
+}
+
+impl<C> ImageDrawable for Bmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_area(target, &self.bounding_box())
+    }
 
     fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
     where
         D: DrawTarget<Color = Self::Color>,
     {
-        self.draw(&mut target.translated(-area.top_left).clipped(area))
+        // Only the part of the area which overlaps the image is decoded.
+        let source = area.intersection(&self.bounding_box());
+        if source.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.draw_area(
+            &mut target.translated(source.top_left - area.top_left),
+            &source,
+        )
     }
 }
 
@@ -402,39 +868,61 @@ where
     type Color = C;
 
     fn pixel(&self, p: Point) -> Option<Self::Color> {
-        match self.raw_bmp.color_type {
-            ColorType::Index1 => self
-                .raw_bmp
-                .color_table()
-                .and_then(|color_table| color_table.get(self.raw_bmp.pixel(p)?))
-                .map(Into::into),
-            ColorType::Index4 => self
-                .raw_bmp
-                .color_table()
-                .and_then(|color_table| color_table.get(self.raw_bmp.pixel(p)?))
-                .map(Into::into),
-            ColorType::Index8 => self
-                .raw_bmp
-                .color_table()
-                .and_then(|color_table| color_table.get(self.raw_bmp.pixel(p)?))
-                .map(Into::into),
-            ColorType::Rgb555 => self
-                .raw_bmp
-                .pixel(p)
-                .map(|raw| Rgb555::from(RawU16::from_u32(raw)).into()),
-            ColorType::Rgb565 => self
-                .raw_bmp
-                .pixel(p)
-                .map(|raw| Rgb565::from(RawU16::from_u32(raw)).into()),
-            ColorType::Rgb888 => self
-                .raw_bmp
-                .pixel(p)
-                .map(|raw| Rgb888::from(RawU24::from_u32(raw)).into()),
-            ColorType::Xrgb8888 => self
-                .raw_bmp
-                .pixel(p)
-                .map(|raw| Rgb888::from(RawU24::from_u32(raw)).into()),
+        let raw = self.raw_bmp.pixel(p)?;
+
+        self.raw_bmp
+            .color_type
+            .to_color(raw, self.raw_bmp.color_table())
+    }
+}
+
+/// Draw target which writes pixels into a single row.
+struct RowTarget<'a, C>(&'a mut [C]);
+
+impl<C: PixelColor> DrawTarget for RowTarget<'_, C> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            if let (0, Ok(x)) = (p.y, usize::try_from(p.x)) {
+                if let Some(pixel) = self.0.get_mut(x) {
+                    *pixel = color;
+                }
+            }
         }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        // All areas which are drawn by `Bmp::draw_area` are inside the row.
+        if let (0, 1, Ok(x)) = (
+            area.top_left.y,
+            area.size.height,
+            usize::try_from(area.top_left.x),
+        ) {
+            let len = area.size.width as usize;
+            for (pixel, color) in self.0.iter_mut().skip(x).take(len).zip(colors) {
+                *pixel = color;
+            }
+
+            return Ok(());
+        }
+
+        self.draw_iter(area.points().zip(colors).map(|(p, color)| Pixel(p, color)))
+    }
+}
+
+impl<C> OriginDimensions for RowTarget<'_, C> {
+    fn size(&self) -> Size {
+        Size::new(self.0.len() as u32, 1)
     }
 }
 
@@ -449,7 +937,8 @@ pub enum ParseError {
 
     /// Invalid file signatures.
     ///
-    /// BMP files must start with `BM`.
+    /// BMP files must start with `BM`. For ICO and CUR files the value of the resource type field
+    /// is returned, which must be `1` or `2`.
     InvalidFileSignature([u8; 2]),
 
     /// Unsupported compression method.
@@ -459,6 +948,9 @@ pub enum ParseError {
     UnsupportedHeaderLength(u32),
 
     /// Unsupported channel masks.
+    ///
+    /// Channel masks are unsupported if the set bits in a mask aren't contiguous or if a mask
+    /// exceeds the bit depth of the image.
     UnsupportedChannelMasks,
 
     /// Invalid image dimensions.
@@ -529,13 +1021,13 @@ mod tests {
 
     #[test]
     fn error_compression_method() {
-        // Replace compression method with BI_JPEG (4).
+        // Replace compression method with BI_CMYK (11).
         let mut data = bmp_data();
-        data[0x1E..0x1E + 4].copy_from_slice(&4u32.to_le_bytes());
+        data[0x1E..0x1E + 4].copy_from_slice(&11u32.to_le_bytes());
 
         assert_eq!(
             Bmp::<Rgb888>::from_slice(&data),
-            Err(ParseError::UnsupportedCompressionMethod(4))
+            Err(ParseError::UnsupportedCompressionMethod(11))
         );
     }
 
@@ -0,0 +1,55 @@
+//! Precomputed color lookup tables for indexed images.
+
+use crate::raw_bmp::ColorType;
+
+/// Number of entries in a lookup table.
+///
+/// Tables with 256 entries are only used if the `large-lut` feature is enabled. Otherwise the
+/// table only covers images with up to 4 bpp and the colors of larger indices are converted for
+/// each pixel.
+#[cfg(feature = "large-lut")]
+const LOOKUP_TABLE_LEN: usize = 256;
+#[cfg(not(feature = "large-lut"))]
+const LOOKUP_TABLE_LEN: usize = 16;
+
+/// Lookup table, which contains the result of a function for all color indices of an image.
+pub(crate) struct LookupTable<T, F> {
+    table: [T; LOOKUP_TABLE_LEN],
+    f: F,
+}
+
+impl<T, F> LookupTable<T, F>
+where
+    T: Copy,
+    F: Fn(u8) -> T,
+{
+    /// Creates a new lookup table.
+    ///
+    /// Only the indices which can be used by images with the given color type are passed to `f`.
+    /// All other entries are set to the result for index `0`.
+    pub(crate) fn new(color_type: ColorType, f: F) -> Self {
+        let len = match color_type {
+            ColorType::Index1 => 2,
+            ColorType::Index2 => 4,
+            ColorType::Index4 => 16,
+            ColorType::Index8 => 256,
+            _ => 1,
+        };
+
+        let mut table = [f(0); LOOKUP_TABLE_LEN];
+        for (index, entry) in (0..len).zip(table.iter_mut()).skip(1) {
+            *entry = f(index as u8);
+        }
+
+        Self { table, f }
+    }
+
+    /// Returns the entry for a color index.
+    #[inline]
+    pub(crate) fn get(&self, index: u8) -> T {
+        self.table
+            .get(usize::from(index))
+            .copied()
+            .unwrap_or_else(|| (self.f)(index))
+    }
+}
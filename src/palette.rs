@@ -0,0 +1,128 @@
+use embedded_graphics::{
+    image::GetPixel,
+    pixelcolor::{Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{lookup_table::LookupTable, Bmp};
+
+/// BMP image which is drawn with a replacement palette.
+///
+/// This struct is created by [`Bmp::with_palette`] and can be drawn to any [`DrawTarget`] by
+/// wrapping it in an [`Image`](embedded_graphics::image::Image). The palette is used instead of
+/// the color table of indexed images, which makes it possible to change the colors of an image at
+/// runtime without modifying the image data.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PaletteBmp<'a, C> {
+    bmp: Bmp<'a, C>,
+    palette: &'a [C],
+}
+
+impl<'a, C> PaletteBmp<'a, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    pub(crate) const fn new(bmp: Bmp<'a, C>, palette: &'a [C]) -> Self {
+        Self { bmp, palette }
+    }
+
+    /// Returns a reference to the wrapped BMP image.
+    pub const fn as_bmp(&self) -> &Bmp<'a, C> {
+        &self.bmp
+    }
+
+    /// Returns the palette.
+    pub const fn palette(&self) -> &'a [C] {
+        self.palette
+    }
+
+    /// Returns the color of a color index.
+    ///
+    /// Indices which are out of bounds of the palette use the color table entry of the image.
+    fn color(&self, index: u8) -> C {
+        self.palette
+            .get(usize::from(index))
+            .copied()
+            .or_else(|| {
+                let color_table = self.bmp.as_raw().color_table()?;
+                color_table.get(u32::from(index)).map(Into::into)
+            })
+            .unwrap_or_else(|| Rgb888::BLACK.into())
+    }
+
+    /// Draws a part of the image.
+    ///
+    /// The top left corner of `area` is drawn at the origin of `target`. `area` must be inside
+    /// the image bounding box.
+    fn draw_area<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if !self.bmp.is_indexed() {
+            return self.bmp.draw_area(target, area);
+        }
+
+        let lookup_table =
+            LookupTable::new(self.bmp.as_raw().color_type, |index| self.color(index));
+
+        self.bmp
+            .draw_indexed_area(target, area, |index| lookup_table.get(index))
+    }
+}
+
+impl<C> ImageDrawable for PaletteBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_area(target, &self.bounding_box())
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let source = area.intersection(&self.bounding_box());
+        if source.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.draw_area(
+            &mut target.translated(source.top_left - area.top_left),
+            &source,
+        )
+    }
+}
+
+impl<C> OriginDimensions for PaletteBmp<'_, C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.bmp.size()
+    }
+}
+
+impl<C> GetPixel for PaletteBmp<'_, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        if self.bmp.is_indexed() {
+            self.bmp
+                .as_raw()
+                .pixel(p)
+                .map(|index| self.color(index as u8))
+        } else {
+            self.bmp.pixel(p)
+        }
+    }
+}
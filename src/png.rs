@@ -0,0 +1,482 @@
+//! Decoder for PNG images embedded in BMP files.
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{image::GetPixel, pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+use miniz_oxide::inflate::{
+    core::{decompress, inflate_flags, DecompressorOxide},
+    TINFLStatus,
+};
+
+/// PNG file signature.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// PNG decode error.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum PngError {
+    /// The data doesn't start with a PNG file signature.
+    InvalidFileSignature,
+
+    /// The PNG file is truncated or contains invalid data.
+    InvalidData,
+
+    /// The PNG file uses an unsupported bit depth, color type or interlacing.
+    UnsupportedFormat,
+
+    /// The buffer is too small to hold the decoded image.
+    ///
+    /// The required length can be determined by using [`PngImage::buffer_len`].
+    BufferTooSmall,
+}
+
+/// PNG color type.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+enum PngColorType {
+    Gray,
+    Rgb,
+    Indexed,
+    GrayAlpha,
+    Rgba,
+}
+
+impl PngColorType {
+    const fn new(value: u8) -> Result<Self, PngError> {
+        Ok(match value {
+            0 => Self::Gray,
+            2 => Self::Rgb,
+            3 => Self::Indexed,
+            4 => Self::GrayAlpha,
+            6 => Self::Rgba,
+            _ => return Err(PngError::UnsupportedFormat),
+        })
+    }
+
+    /// Returns the number of bytes per pixel.
+    const fn channels(self) -> usize {
+        match self {
+            Self::Gray | Self::Indexed => 1,
+            Self::GrayAlpha => 2,
+            Self::Rgb => 3,
+            Self::Rgba => 4,
+        }
+    }
+}
+
+/// Information from the `IHDR` chunk.
+#[derive(Debug, Copy, Clone)]
+struct ImageHeader {
+    size: Size,
+    color_type: PngColorType,
+}
+
+impl ImageHeader {
+    /// Returns the row length in bytes, without the filter type byte.
+    fn stride(&self) -> Result<usize, PngError> {
+        (self.size.width as usize)
+            .checked_mul(self.color_type.channels())
+            .ok_or(PngError::UnsupportedFormat)
+    }
+
+    /// Returns the length of the inflated image data, including the filter type bytes.
+    fn filtered_len(&self) -> Result<usize, PngError> {
+        (self.stride()? + 1)
+            .checked_mul(self.size.height as usize)
+            .ok_or(PngError::UnsupportedFormat)
+    }
+}
+
+/// Iterator over the chunks in a PNG file.
+#[derive(Debug, Clone)]
+struct Chunks<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Chunks<'a> {
+    fn new(png: &'a [u8]) -> Result<Self, PngError> {
+        match png.strip_prefix(PNG_SIGNATURE) {
+            Some(data) => Ok(Self { data }),
+            None => Err(PngError::InvalidFileSignature),
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Result<([u8; 4], &'a [u8]), PngError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        // The chunk length and type are followed by the chunk data and a 4 byte CRC, which isn't
+        // checked.
+        let chunk = match self.data {
+            [l0, l1, l2, l3, t0, t1, t2, t3, rest @ ..] => {
+                let length = u32::from_be_bytes([*l0, *l1, *l2, *l3]) as usize;
+                match length.checked_add(4) {
+                    Some(end) if end <= rest.len() => {
+                        self.data = &rest[end..];
+                        Ok(([*t0, *t1, *t2, *t3], &rest[..length]))
+                    }
+                    _ => Err(PngError::InvalidData),
+                }
+            }
+            _ => Err(PngError::InvalidData),
+        };
+
+        if chunk.is_err() {
+            self.data = &[];
+        }
+
+        Some(chunk)
+    }
+}
+
+/// Parses the `IHDR` chunk, which must be the first chunk in a PNG file.
+fn parse_header(png: &[u8]) -> Result<ImageHeader, PngError> {
+    let (chunk_type, data) = Chunks::new(png)?.next().ok_or(PngError::InvalidData)??;
+
+    match (&chunk_type, data) {
+        (
+            b"IHDR",
+            &[w0, w1, w2, w3, h0, h1, h2, h3, bit_depth, color_type, compression, filter, interlace],
+        ) => {
+            let width = u32::from_be_bytes([w0, w1, w2, w3]);
+            let height = u32::from_be_bytes([h0, h1, h2, h3]);
+
+            if width == 0 || height == 0 || compression != 0 || filter != 0 {
+                return Err(PngError::InvalidData);
+            }
+
+            if bit_depth != 8 || interlace != 0 {
+                return Err(PngError::UnsupportedFormat);
+            }
+
+            Ok(ImageHeader {
+                size: Size::new(width, height),
+                color_type: PngColorType::new(color_type)?,
+            })
+        }
+        _ => Err(PngError::InvalidData),
+    }
+}
+
+/// PNG image.
+///
+/// This decoder can be used to decode images with the
+/// [`CompressionMethod::Png`](crate::CompressionMethod::Png) compression method, which contain an
+/// embedded PNG image instead of pixel data. The embedded image can be accessed by using
+/// [`RawBmp::embedded_image`](crate::RawBmp::embedded_image).
+///
+/// Only non-interlaced images with a bit depth of 8 bits per channel are supported. The image is
+/// decoded into a caller provided buffer, which must be at least
+/// [`buffer_len`](Self::buffer_len) bytes long. The decoder doesn't allocate memory, but it uses
+/// about 11 KiB of stack space for the inflate state.
+///
+/// Like [`Bmp`](crate::Bmp), the alpha channel is ignored when the image is drawn, but the alpha
+/// value of a pixel can be accessed by using [`alpha`](Self::alpha).
+///
+/// This type is only available if the `png` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb888, prelude::*};
+/// use tinybmp::{PngImage, RawBmp};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb888> = MockDisplay::default();
+///
+/// let bmp = RawBmp::from_slice(include_bytes!("../tests/png_rgb.bmp")).unwrap();
+/// let png = bmp.embedded_image().unwrap();
+///
+/// let mut buffer = [0; 1024];
+/// let image = PngImage::<Rgb888>::decode(png, &mut buffer).unwrap();
+///
+/// Image::new(&image, Point::zero()).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PngImage<'a, C> {
+    size: Size,
+    color_type: PngColorType,
+    palette: &'a [u8],
+    transparency: &'a [u8],
+    data: &'a [u8],
+    target_color_type: PhantomData<C>,
+}
+
+impl<'a, C> PngImage<'a, C>
+where
+    C: PixelColor + From<Rgb888>,
+{
+    /// Returns the buffer length in bytes which is required to decode a PNG image.
+    ///
+    /// The buffer must be large enough to hold the inflated image data, which contains an
+    /// additional filter type byte at the start of each row.
+    pub fn buffer_len(png: &[u8]) -> Result<usize, PngError> {
+        parse_header(png)?.filtered_len()
+    }
+
+    /// Decodes a PNG image into a buffer.
+    ///
+    /// The decoded pixel data is stored in `buffer`, which must be at least
+    /// [`buffer_len`](Self::buffer_len) bytes long.
+    pub fn decode(png: &'a [u8], buffer: &'a mut [u8]) -> Result<Self, PngError> {
+        let header = parse_header(png)?;
+        let stride = header.stride()?;
+        let filtered_len = header.filtered_len()?;
+
+        let buffer = buffer
+            .get_mut(..filtered_len)
+            .ok_or(PngError::BufferTooSmall)?;
+
+        let mut palette: &[u8] = &[];
+        let mut transparency: &[u8] = &[];
+
+        let mut decompressor = DecompressorOxide::new();
+        let mut out_pos = 0;
+        let mut done = false;
+
+        for chunk in Chunks::new(png)?.skip(1) {
+            let (chunk_type, data) = chunk?;
+
+            match &chunk_type {
+                b"PLTE" => palette = data,
+                b"tRNS" => transparency = data,
+                b"IDAT" if !done => {
+                    let mut input = data;
+                    loop {
+                        let (status, bytes_read, bytes_written) = decompress(
+                            &mut decompressor,
+                            input,
+                            buffer,
+                            out_pos,
+                            inflate_flags::TINFL_FLAG_PARSE_ZLIB_HEADER
+                                | inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF
+                                | inflate_flags::TINFL_FLAG_HAS_MORE_INPUT,
+                        );
+                        input = &input[bytes_read..];
+                        out_pos += bytes_written;
+
+                        match status {
+                            TINFLStatus::Done => {
+                                done = true;
+                                break;
+                            }
+                            TINFLStatus::NeedsMoreInput if input.is_empty() => break,
+                            TINFLStatus::NeedsMoreInput => {}
+                            _ => return Err(PngError::InvalidData),
+                        }
+                    }
+                }
+                b"IEND" => break,
+                _ => {}
+            }
+        }
+
+        if !done || out_pos != filtered_len {
+            return Err(PngError::InvalidData);
+        }
+
+        unfilter(buffer, stride, header.color_type.channels())?;
+        let data = &buffer[..stride * header.size.height as usize];
+
+        if header.color_type == PngColorType::Indexed {
+            let num_entries = palette.len() / 3;
+            if num_entries * 3 != palette.len()
+                || data.iter().any(|&i| usize::from(i) >= num_entries)
+            {
+                return Err(PngError::InvalidData);
+            }
+        } else {
+            transparency = &[];
+        }
+
+        Ok(Self {
+            size: header.size,
+            color_type: header.color_type,
+            palette,
+            transparency,
+            data,
+            target_color_type: PhantomData,
+        })
+    }
+
+    /// Returns `true` if the image contains an alpha channel or palette transparency.
+    pub fn has_alpha(&self) -> bool {
+        matches!(
+            self.color_type,
+            PngColorType::GrayAlpha | PngColorType::Rgba
+        ) || !self.transparency.is_empty()
+    }
+
+    /// Returns the alpha value of a pixel.
+    ///
+    /// Pixels in images without an alpha channel are fully opaque and return `255`. `None` is
+    /// returned if the point is outside the image.
+    pub fn alpha(&self, p: Point) -> Option<u8> {
+        let pixel = self.raw_pixel(p)?;
+
+        Some(match self.color_type {
+            PngColorType::GrayAlpha => pixel[1],
+            PngColorType::Rgba => pixel[3],
+            PngColorType::Indexed => *self.transparency.get(usize::from(pixel[0])).unwrap_or(&255),
+            PngColorType::Gray | PngColorType::Rgb => 255,
+        })
+    }
+
+    /// Returns the bytes of a pixel.
+    fn raw_pixel(&self, p: Point) -> Option<&'a [u8]> {
+        if p.x < 0 || p.y < 0 || p.x as u32 >= self.size.width || p.y as u32 >= self.size.height {
+            return None;
+        }
+
+        let channels = self.color_type.channels();
+        let start = (p.y as usize * self.size.width as usize + p.x as usize) * channels;
+        self.data.get(start..start + channels)
+    }
+
+    /// Converts the bytes of a pixel into a color.
+    fn color(&self, pixel: &[u8]) -> C {
+        let color = match (self.color_type, pixel) {
+            (PngColorType::Rgb | PngColorType::Rgba, &[r, g, b, ..]) => Rgb888::new(r, g, b),
+            (PngColorType::Indexed, &[index]) => {
+                let start = usize::from(index) * 3;
+                match self.palette.get(start..start + 3) {
+                    Some(&[r, g, b]) => Rgb888::new(r, g, b),
+                    _ => Rgb888::BLACK,
+                }
+            }
+            (_, &[luma, ..]) => Rgb888::new(luma, luma, luma),
+            _ => Rgb888::BLACK,
+        };
+
+        color.into()
+    }
+}
+
+/// Reverses the PNG row filters and removes the filter type bytes.
+///
+/// The unfiltered rows are moved to the start of the buffer.
+fn unfilter(buffer: &mut [u8], stride: usize, bytes_per_pixel: usize) -> Result<(), PngError> {
+    let height = buffer.len() / (stride + 1);
+
+    for y in 0..height {
+        let filter_type = buffer[y * (stride + 1)];
+        let row = y * (stride + 1) + 1;
+        // The previous row has already been moved to its final position.
+        let previous = y.checked_sub(1).map(|y| y * stride);
+
+        for x in 0..stride {
+            let a = if x >= bytes_per_pixel {
+                buffer[row + x - bytes_per_pixel]
+            } else {
+                0
+            };
+            let b = previous.map_or(0, |previous| buffer[previous + x]);
+            let c = match previous {
+                Some(previous) if x >= bytes_per_pixel => buffer[previous + x - bytes_per_pixel],
+                _ => 0,
+            };
+
+            let predictor = match filter_type {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((u16::from(a) + u16::from(b)) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(PngError::InvalidData),
+            };
+
+            buffer[row + x] = buffer[row + x].wrapping_add(predictor);
+        }
+
+        buffer.copy_within(row..row + stride, y * stride);
+    }
+
+    Ok(())
+}
+
+/// Paeth predictor.
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let pa = (p - i16::from(a)).abs();
+    let pb = (p - i16::from(b)).abs();
+    let pc = (p - i16::from(c)).abs();
+
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+impl<C> ImageDrawable for PngImage<'_, C>
+where
+    C: PixelColor + From<Rgb888>,
+{
+    type Color = C;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let channels = self.color_type.channels();
+        target.fill_contiguous(
+            &self.bounding_box(),
+            self.data
+                .chunks_exact(channels)
+                .map(|pixel| self.color(pixel)),
+        )
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut target.translated(-area.top_left).clipped(area))
+    }
+}
+
+impl<C> OriginDimensions for PngImage<'_, C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C> GetPixel for PngImage<'_, C>
+where
+    C: PixelColor + From<Rgb888>,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        self.raw_pixel(p).map(|pixel| self.color(pixel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paeth_predictor() {
+        assert_eq!(paeth(10, 20, 10), 20);
+        assert_eq!(paeth(20, 10, 10), 20);
+        assert_eq!(paeth(10, 10, 20), 10);
+        assert_eq!(paeth(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn invalid_signature() {
+        assert_eq!(
+            PngImage::<Rgb888>::buffer_len(b"BM"),
+            Err(PngError::InvalidFileSignature)
+        );
+    }
+}
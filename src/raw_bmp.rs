@@ -1,15 +1,20 @@
 use embedded_graphics::{
     geometry::Point,
     iterator::raw::RawDataSlice,
-    pixelcolor::raw::{LittleEndian, RawU1, RawU16, RawU24, RawU32, RawU4, RawU8},
-    prelude::RawData,
+    pixelcolor::{
+        raw::{LittleEndian, RawU1, RawU16, RawU2, RawU24, RawU32, RawU4, RawU8},
+        Rgb555, Rgb565, Rgb888,
+    },
+    prelude::{IntoStorage, PixelColor, RawData},
+    primitives::Rectangle,
 };
 
 use crate::{
-    color_table::ColorTable,
-    header::{Bpp, Header},
-    raw_iter::{DynamicRawColors, RawPixels},
-    try_const, ChannelMasks, ParseError, RowOrder,
+    bitfields::Bitfields,
+    color_table::{nearest_color_index, ColorTable},
+    header::{Bpp, CompressionMethod, Header, FILE_HEADER_LEN},
+    raw_iter::{DynamicRawColors, RawPixels, RawRows, Rle4Colors, Rle8Colors, RleRows},
+    scrgb, try_const, ChannelMasks, IccProfile, ParseError, RawFormat, RowOrder,
 };
 
 /// Low-level access to BMP image data.
@@ -32,6 +37,12 @@ pub struct RawBmp<'a> {
 
     /// Image data.
     image_data: &'a [u8],
+
+    /// Row index for RLE compressed images.
+    rle_index: Option<&'a [u32]>,
+
+    /// Embedded or linked ICC profile.
+    icc_profile: Option<IccProfile<'a>>,
 }
 
 impl<'a> RawBmp<'a> {
@@ -42,6 +53,32 @@ impl<'a> RawBmp<'a> {
     pub const fn from_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
         let (_remaining, (header, color_table)) = try_const!(Header::parse(bytes));
 
+        Self::new(bytes, FILE_HEADER_LEN, header, color_table)
+    }
+
+    /// Create a bitmap object from a byte slice which contains a DIB without a file header.
+    ///
+    /// Device independent bitmaps (DIB) without the 14 byte `BITMAPFILEHEADER` are, for example,
+    /// used by the Windows clipboard `CF_DIB` format and inside ICO and CUR files. The slice must
+    /// start with the DIB header. The image data is expected to start directly after the DIB
+    /// header, the channel masks and the color table.
+    ///
+    /// Because there is no file header [`Header::file_size`] is set to the length of `bytes`.
+    pub const fn from_dib_slice(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        let (_remaining, (header, color_table)) = try_const!(Header::parse_dib(bytes));
+
+        Self::new(bytes, 0, header, color_table)
+    }
+
+    /// Creates a bitmap object from a parsed header and the complete input data.
+    ///
+    /// `dib_header_start` is the offset of the DIB header in `bytes`.
+    pub(crate) const fn new(
+        bytes: &'a [u8],
+        dib_header_start: usize,
+        header: Header,
+        color_table: Option<ColorTable<'a>>,
+    ) -> Result<Self, ParseError> {
         let color_type = try_const!(ColorType::from_header(&header));
 
         if bytes.len() < header.image_data_start {
@@ -49,8 +86,13 @@ impl<'a> RawBmp<'a> {
         }
         let (_, image_data) = bytes.split_at(header.image_data_start);
 
-        let data_length = if let crate::header::CompressionMethod::Rgb = header.compression_method {
-            // `Header::image_data_len` may be zero or bogus when compression mode is RGB
+        let data_length = if matches!(
+            header.compression_method,
+            CompressionMethod::Rgb
+                | CompressionMethod::Bitfields
+                | CompressionMethod::AlphaBitfields
+        ) {
+            // `Header::image_data_len` may be zero or bogus for uncompressed images
             // see `biSizeImage` on https://learn.microsoft.com/en-us/previous-versions/dd183376(v=vs.85)
             // so we should calculate width x height instead.
             let height = header.image_size.height as usize;
@@ -71,14 +113,99 @@ impl<'a> RawBmp<'a> {
 
         let (image_data, _) = image_data.split_at(data_length);
 
+        let icc_profile = match &header.color_space {
+            Some(color_space) => IccProfile::parse(
+                bytes,
+                dib_header_start.saturating_add(color_space.profile_offset as usize),
+                color_space,
+            ),
+            None => None,
+        };
+
         Ok(Self {
             header,
             color_type,
             color_table,
             image_data,
+            rle_index: None,
+            icc_profile,
         })
     }
 
+    /// Creates a bitmap object from already parsed parts.
+    pub(crate) const fn from_parts(
+        header: Header,
+        color_type: ColorType,
+        color_table: Option<ColorTable<'a>>,
+        image_data: &'a [u8],
+        icc_profile: Option<IccProfile<'a>>,
+    ) -> Self {
+        Self {
+            header,
+            color_type,
+            color_table,
+            image_data,
+            rle_index: None,
+            icc_profile,
+        }
+    }
+
+    /// Uses a row index to speed up random pixel access in RLE compressed images.
+    ///
+    /// The index must be built by calling [`build_rle_index`](Self::build_rle_index) on the same
+    /// image. With an index [`pixel`](Self::pixel) only needs to decode the pixels in a single row
+    /// instead of all pixels before the accessed pixel. The index is ignored for uncompressed
+    /// images.
+    pub const fn with_rle_index(self, index: &'a [u32]) -> Self {
+        Self {
+            rle_index: Some(index),
+            ..self
+        }
+    }
+
+    /// Builds a row index for an RLE compressed image.
+    ///
+    /// The index must contain one entry per row. Each entry is set to the byte offset of the row in
+    /// the image data. Because RLE compressed images are stored bottom-up, the first entry contains
+    /// the offset of the bottom row. Rows which don't start at the
+    /// beginning of an RLE command, for example because they start in the middle of a run or are
+    /// skipped by a delta escape, are set to [`u32::MAX`]. Lookups in these rows start decoding
+    /// at the closest previous row in the index.
+    ///
+    /// Returns `false` if the image isn't RLE compressed or if `index` is shorter than the image
+    /// height.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics::prelude::*;
+    /// use tinybmp::RawBmp;
+    ///
+    /// # let data = include_bytes!("../tests/logo-indexed-8bpp-rle8.bmp");
+    /// let bmp = RawBmp::from_slice(data).unwrap();
+    ///
+    /// let mut index = [0; 320];
+    /// assert!(bmp.build_rle_index(&mut index));
+    ///
+    /// let indexed_bmp = bmp.with_rle_index(&index);
+    /// let p = Point::new(20, 10);
+    /// assert_eq!(indexed_bmp.pixel(p), bmp.pixel(p));
+    /// ```
+    pub fn build_rle_index(&self, index: &mut [u32]) -> bool {
+        let height = self.header.image_size.height as usize;
+        let Some(index) = index.get_mut(..height) else {
+            return false;
+        };
+
+        match self.header.compression_method {
+            CompressionMethod::Rle8 => build_rle_index::<Rle8Colors<'_>>(self, index),
+            CompressionMethod::Rle4 => build_rle_index::<Rle4Colors<'_>>(self, index),
+            _ => return false,
+        }
+
+        true
+    }
+
     /// Returns the color table associated with the image.
     pub const fn color_table(&self) -> Option<&ColorTable<'a>> {
         self.color_table.as_ref()
@@ -89,6 +216,36 @@ impl<'a> RawBmp<'a> {
         self.image_data
     }
 
+    /// Returns the embedded JPEG or PNG image.
+    ///
+    /// Images which use the [`Jpeg`](CompressionMethod::Jpeg) or [`Png`](CompressionMethod::Png)
+    /// compression methods contain a complete JPEG or PNG file instead of pixel data. These images
+    /// aren't decoded and are drawn as empty images, but the returned slice can be passed to a
+    /// separate decoder. `None` is returned for all other images.
+    pub const fn embedded_image(&self) -> Option<&'a [u8]> {
+        if self.header.compression_method.is_embedded_image() {
+            Some(self.image_data)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ICC color profile.
+    ///
+    /// `BITMAPV5HEADER` headers can either embed an ICC profile into the file or link to an
+    /// external profile by its file name, see [`ColorSpace::EmbeddedProfile`] and
+    /// [`ColorSpace::LinkedProfile`]. The returned slice contains the raw profile data or the file
+    /// name of the linked profile.
+    ///
+    /// Returns `None` if the image doesn't use a profile or if the profile location points outside
+    /// of the input data.
+    ///
+    /// [`ColorSpace::EmbeddedProfile`]: crate::ColorSpace::EmbeddedProfile
+    /// [`ColorSpace::LinkedProfile`]: crate::ColorSpace::LinkedProfile
+    pub const fn icc_profile(&self) -> Option<IccProfile<'a>> {
+        self.icc_profile
+    }
+
     /// Returns a reference to the BMP header.
     pub const fn header(&self) -> &Header {
         &self.header
@@ -103,32 +260,74 @@ impl<'a> RawBmp<'a> {
         RawPixels::new(self)
     }
 
+    /// Returns an iterator over the rows in the image.
+    ///
+    /// The rows are returned in top-down order, regardless of the [`RowOrder`] of the image data.
+    /// Each row contains the raw pixel data without the padding bytes at the end of each row in
+    /// the file, which makes it possible to pass the rows directly to a display driver or another
+    /// library.
+    ///
+    /// RLE compressed images and images with an [`embedded_image`](Self::embedded_image) don't
+    /// contain rows of raw pixels and the returned iterator is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tinybmp::RawBmp;
+    ///
+    /// # let data = include_bytes!("../tests/chessboard-8px-24bit.bmp");
+    /// let bmp = RawBmp::from_slice(data).unwrap();
+    ///
+    /// for row in bmp.rows() {
+    ///     // 8 pixels with 3 bytes per pixel.
+    ///     assert_eq!(row.data.len(), 24);
+    /// }
+    /// ```
+    pub fn rows(&self) -> RawRows<'a> {
+        RawRows::new(self)
+    }
+
     /// Returns an iterator over the raw colors in the image.
     ///
     /// The iterator returns the color value in the order the pixels are stored in the file.
     /// Use [`row_order`](DynamicRawColors::row_order) to determine the correct
-    /// pixel arrangement.
+    /// pixel arrangement. Undefined pixels in RLE compressed images, which are skipped by delta
+    /// escapes or end of line markers, are returned as `0`.
     pub fn colors(&self) -> DynamicRawColors<'_> {
         self.pixels().colors
     }
 
+    /// Returns `true` if the image contains an alpha channel.
+    ///
+    /// Only 16 and 32 bpp images with an alpha channel mask in the header and 64 bpp images contain
+    /// alpha values.
+    pub const fn has_alpha(&self) -> bool {
+        self.color_type.has_alpha()
+    }
+
+    /// Returns the alpha value of a pixel.
+    ///
+    /// Returns `None` if `p` is outside the image bounding box. For images without an alpha
+    /// channel the returned value is always `255`, which represents a fully opaque pixel.
+    pub fn alpha(&self, p: Point) -> Option<u8> {
+        self.pixel(p).map(|raw| self.color_type.alpha(raw))
+    }
+
     /// Returns the raw color of a pixel.
     ///
     /// Returns `None` if `p` is outside the image bounding box. Note that this function doesn't
     /// apply a color map, if the image contains one.
     ///
-    /// This routine always returns `None` if the bitmap is RLE compressed, as RLE compressed
-    /// bitmaps don't easily allow direct access to any given pixel.
+    /// RLE compressed bitmaps don't allow direct access to any given pixel and need to be decoded
+    /// up to the accessed pixel, which makes this method `O(n)` in the number of pixels in the
+    /// image. Lookups can be sped up to `O(width)` by using a row index, see
+    /// [`build_rle_index`](Self::build_rle_index). Undefined pixels in RLE compressed images,
+    /// which are skipped by delta escapes or end of line markers, return `None`.
+    ///
+    /// Images with an [`embedded_image`](Self::embedded_image) always return `None`. Pixels in
+    /// 64 bpp images are converted into `ARGB8888` values, use [`pixel_wide`](Self::pixel_wide) to
+    /// access the unconverted values.
     pub fn pixel(&self, p: Point) -> Option<u32> {
-        if matches!(
-            self.header.compression_method,
-            crate::header::CompressionMethod::Rle8 | crate::header::CompressionMethod::Rle4
-        ) {
-            // TODO implement direct access by counting `0x00, 0x00` pairs,
-            // which uniquely mark the end of a line.
-            return None;
-        }
-
         let width = self.header.image_size.width as i32;
         let height = self.header.image_size.height as i32;
 
@@ -136,6 +335,13 @@ impl<'a> RawBmp<'a> {
             return None;
         }
 
+        match self.header.compression_method {
+            CompressionMethod::Rle8 => return rle_pixel::<Rle8Colors<'_>, _>(self, p),
+            CompressionMethod::Rle4 => return rle_pixel::<Rle4Colors<'_>, _>(self, p),
+            CompressionMethod::Jpeg | CompressionMethod::Png => return None,
+            _ => {}
+        }
+
         // The specialized implementations of `Iterator::nth` for `Chunks` and
         // `RawDataSlice::IntoIter` are `O(1)`, which also makes this method `O(1)`.
 
@@ -150,6 +356,10 @@ impl<'a> RawBmp<'a> {
                 .into_iter()
                 .nth(p.x as usize)
                 .map(|raw| u32::from(raw.into_inner())),
+            Bpp::Bits2 => RawDataSlice::<RawU2, LittleEndian>::new(row)
+                .into_iter()
+                .nth(p.x as usize)
+                .map(|raw| u32::from(raw.into_inner())),
             Bpp::Bits4 => RawDataSlice::<RawU4, LittleEndian>::new(row)
                 .into_iter()
                 .nth(p.x as usize)
@@ -170,25 +380,169 @@ impl<'a> RawBmp<'a> {
                 .into_iter()
                 .nth(p.x as usize)
                 .map(|raw| raw.into_inner()),
+            Bpp::Bits64 => wide_pixel(row, p.x as usize).map(scrgb::to_argb8888),
+        }
+    }
+
+    /// Returns the unconverted raw color of a pixel in a 64 bpp image.
+    ///
+    /// 64 bpp images store each pixel as four 16 bit signed fixed point values with 13 fractional
+    /// bits. The returned value contains the blue, green, red and alpha channels in order of
+    /// increasing significance. The color channels use linear gamma and values outside of the
+    /// range `0.0..=1.0` (`0..=0x2000`) are used for colors outside of the sRGB gamut.
+    ///
+    /// All other methods convert 64 bpp pixels into `ARGB8888` values, with 8 bit sRGB color
+    /// channels. For images with other bit depths this method returns the same values as
+    /// [`pixel`](Self::pixel).
+    pub fn pixel_wide(&self, p: Point) -> Option<u64> {
+        if self.header.bpp != Bpp::Bits64 {
+            return self.pixel(p).map(u64::from);
+        }
+
+        let width = self.header.image_size.width as i32;
+        let height = self.header.image_size.height as i32;
+
+        if p.x < 0 || p.x >= width || p.y < 0 || p.y >= height {
+            return None;
         }
+
+        let mut row_chunks = self.image_data.chunks_exact(self.header.bytes_per_row());
+        let row = match self.header.row_order {
+            RowOrder::BottomUp => row_chunks.nth_back(p.y as usize),
+            RowOrder::TopDown => row_chunks.nth(p.y as usize),
+        }?;
+
+        wide_pixel(row, p.x as usize)
     }
 }
 
+/// Returns the raw 64 bit value of a pixel in a row.
+fn wide_pixel(row: &[u8], x: usize) -> Option<u64> {
+    let bytes = row.get(x * 8..x * 8 + 8)?;
+
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Builds a row index for an RLE compressed image.
+fn build_rle_index<'a, D: RleRows<'a>>(raw_bmp: &RawBmp<'a>, index: &mut [u32]) {
+    index.fill(u32::MAX);
+
+    let mut decoder = D::at_row_start(raw_bmp, 0, 0);
+    loop {
+        if let Some((row, offset)) = decoder.row_start() {
+            if let Some(entry) = index.get_mut(row as usize) {
+                *entry = offset as u32;
+            }
+        }
+
+        if decoder.next().is_none() {
+            break;
+        }
+    }
+}
+
+/// Creates an RLE decoder which starts at or before the given row.
+///
+/// Decoding starts at the closest previous row in the row index or at the start of the image data
+/// if no index is available. Returns the decoder and the row at which decoding starts.
+fn rle_decoder<'a, D: RleRows<'a>>(raw_bmp: &RawBmp<'a>, row: u32) -> (D, u32) {
+    let (start_row, offset) = raw_bmp
+        .rle_index
+        .and_then(|index| {
+            index
+                .get(..=row as usize)?
+                .iter()
+                .enumerate()
+                .rev()
+                .find(|(_, offset)| **offset != u32::MAX)
+        })
+        .map_or((0, 0), |(row, offset)| (row as u32, *offset as usize));
+
+    (D::at_row_start(raw_bmp, start_row, offset), start_row)
+}
+
+/// Returns the raw color of a pixel in an RLE compressed image.
+///
+/// `p` must be inside the image bounding box.
+fn rle_pixel<'a, D, R>(raw_bmp: &RawBmp<'a>, p: Point) -> Option<u32>
+where
+    D: RleRows<'a> + Iterator<Item = Option<R>>,
+    R: RawData<Storage = u8>,
+{
+    let size = raw_bmp.header.image_size;
+
+    // RLE compressed images are always stored bottom-up.
+    let row = size.height - 1 - p.y as u32;
+    let (mut decoder, start_row) = rle_decoder::<D>(raw_bmp, row);
+
+    let n = (row - start_row) as usize * size.width as usize + p.x as usize;
+
+    decoder
+        .nth(n)
+        .flatten()
+        .map(|raw| u32::from(raw.into_inner()))
+}
+
+/// Returns an iterator over the pixels in a part of an RLE compressed image.
+///
+/// The pixels are returned in bottom-up order, like the pixels returned by the RLE decoders.
+/// Decoding starts at the bottom row of the area and the pixels outside of the area are skipped.
+/// `area` must be inside the image bounding box.
+pub(crate) fn rle_area<'a, D>(
+    raw_bmp: &RawBmp<'a>,
+    area: &Rectangle,
+) -> impl Iterator<Item = D::Item>
+where
+    D: RleRows<'a>,
+{
+    let image_width = raw_bmp.header.image_size.width as usize;
+    let width = area.size.width as usize;
+    let len = width * area.size.height as usize;
+
+    // RLE compressed images are always stored bottom-up.
+    let bottom_right = area.bottom_right().unwrap_or(area.top_left);
+    let row = raw_bmp.header.image_size.height - 1 - bottom_right.y as u32;
+    let (mut decoder, start_row) = rle_decoder::<D>(raw_bmp, row);
+
+    let first = (row - start_row) as usize * image_width + area.top_left.x as usize;
+
+    (0..len).map_while(move |i| {
+        let n = if i == 0 {
+            first
+        } else if i % width == 0 {
+            // Skip the pixels to the right of the area in the previous row and to the left of the
+            // area in the next row.
+            image_width - width
+        } else {
+            0
+        };
+
+        decoder.nth(n)
+    })
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum ColorType {
     Index1,
+    Index2,
     Index4,
     Index8,
     Rgb555,
     Rgb565,
     Rgb888,
     Xrgb8888,
+    Argb8888,
+    Bitfields16(Bitfields),
+    Bitfields32(Bitfields),
+    /// 64 bpp scRGB, with raw pixel values converted into `ARGB8888`.
+    Rgb64,
 }
 
 impl ColorType {
     pub(crate) const fn from_header(header: &Header) -> Result<ColorType, ParseError> {
         Ok(match header.bpp {
             Bpp::Bits1 => ColorType::Index1,
+            Bpp::Bits2 => ColorType::Index2,
             Bpp::Bits4 => ColorType::Index4,
             Bpp::Bits8 => ColorType::Index8,
             Bpp::Bits16 => {
@@ -196,7 +550,7 @@ impl ColorType {
                     match masks {
                         ChannelMasks::RGB555 => ColorType::Rgb555,
                         ChannelMasks::RGB565 => ColorType::Rgb565,
-                        _ => return Err(ParseError::UnsupportedChannelMasks),
+                        _ => ColorType::Bitfields16(try_const!(Bitfields::new(&masks, 16))),
                     }
                 } else {
                     // According to the GDI docs the default 16 bpp color format is Rgb555 if no
@@ -210,13 +564,100 @@ impl ColorType {
                 if let Some(masks) = header.channel_masks {
                     if let ChannelMasks::RGB888 = masks {
                         ColorType::Xrgb8888
+                    } else if let ChannelMasks::ARGB8888 = masks {
+                        ColorType::Argb8888
                     } else {
-                        return Err(ParseError::UnsupportedChannelMasks);
+                        ColorType::Bitfields32(try_const!(Bitfields::new(&masks, 32)))
                     }
                 } else {
                     ColorType::Xrgb8888
                 }
             }
+            Bpp::Bits64 => {
+                // The channel masks can't describe 16 bit channels in 64 bit pixels.
+                if header.channel_masks.is_some() {
+                    return Err(ParseError::UnsupportedChannelMasks);
+                }
+                ColorType::Rgb64
+            }
         })
     }
+
+    /// Returns the raw format of the image data, if it matches a [`RawFormat`].
+    pub(crate) const fn raw_format(self) -> Option<RawFormat> {
+        match self {
+            ColorType::Rgb555 => Some(RawFormat::Rgb555Le),
+            ColorType::Rgb565 => Some(RawFormat::Rgb565Le),
+            ColorType::Rgb888 => Some(RawFormat::Rgb888Le),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the color type includes an alpha channel.
+    pub(crate) const fn has_alpha(self) -> bool {
+        match self {
+            ColorType::Argb8888 | ColorType::Rgb64 => true,
+            ColorType::Bitfields16(bitfields) | ColorType::Bitfields32(bitfields) => {
+                bitfields.has_alpha()
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts a color into a raw pixel value.
+    ///
+    /// Colors in indexed images are replaced by the index of the closest color table entry. The
+    /// alpha channel, if present, is set to fully opaque.
+    pub(crate) fn raw_from_rgb888(self, color: Rgb888, color_table: Option<&ColorTable>) -> u32 {
+        match self {
+            ColorType::Index1 | ColorType::Index2 | ColorType::Index4 | ColorType::Index8 => {
+                color_table
+                    .and_then(|color_table| nearest_color_index(color, color_table.entries()))
+                    .unwrap_or(0)
+            }
+            ColorType::Rgb555 => u32::from(Rgb555::from(color).into_storage()),
+            ColorType::Rgb565 => u32::from(Rgb565::from(color).into_storage()),
+            ColorType::Rgb888 | ColorType::Xrgb8888 => color.into_storage(),
+            ColorType::Argb8888 | ColorType::Rgb64 => color.into_storage() | 0xFF00_0000,
+            ColorType::Bitfields16(bitfields) | ColorType::Bitfields32(bitfields) => {
+                bitfields.raw_from_rgb888(color)
+            }
+        }
+    }
+
+    /// Converts a raw pixel value into a color.
+    ///
+    /// Color indices are looked up in the color table. Indices which are out of bounds are
+    /// converted into black. Returns `None` for indexed images without a color table.
+    pub(crate) fn to_color<C>(self, raw: u32, color_table: Option<&ColorTable<'_>>) -> Option<C>
+    where
+        C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+    {
+        Some(match self {
+            ColorType::Index1 | ColorType::Index2 | ColorType::Index4 | ColorType::Index8 => {
+                color_table?.get(raw).unwrap_or_default().into()
+            }
+            ColorType::Rgb555 => Rgb555::from(RawU16::from_u32(raw)).into(),
+            ColorType::Rgb565 => Rgb565::from(RawU16::from_u32(raw)).into(),
+            ColorType::Rgb888 | ColorType::Xrgb8888 | ColorType::Argb8888 | ColorType::Rgb64 => {
+                Rgb888::from(RawU24::from_u32(raw)).into()
+            }
+            ColorType::Bitfields16(bitfields) | ColorType::Bitfields32(bitfields) => {
+                bitfields.to_rgb888(raw).into()
+            }
+        })
+    }
+
+    /// Returns the alpha value of a raw pixel value.
+    ///
+    /// Returns `255` (opaque) for color types without an alpha channel.
+    pub(crate) const fn alpha(self, raw: u32) -> u8 {
+        match self {
+            ColorType::Argb8888 | ColorType::Rgb64 => (raw >> 24) as u8,
+            ColorType::Bitfields16(bitfields) | ColorType::Bitfields32(bitfields) => {
+                bitfields.alpha(raw)
+            }
+            _ => 255,
+        }
+    }
 }
@@ -0,0 +1,165 @@
+use embedded_graphics::geometry::Point;
+
+use crate::{
+    color_table::ColorTable,
+    header::{Bpp, CompressionMethod, Header, FILE_HEADER_LEN},
+    raw_bmp::ColorType,
+    IccProfile, ParseError, RawBmp, RowOrder,
+};
+
+/// Low-level mutable access to BMP image data.
+///
+/// This struct wraps a mutable byte slice which contains a BMP file and allows the pixel data to
+/// be modified in place. The header and color table are read only. Use
+/// [`BmpFramebuffer`](crate::BmpFramebuffer) to draw into the image by using
+/// [`embedded_graphics`].
+///
+/// Only uncompressed images are supported, because the size of RLE compressed image data depends
+/// on the pixel values.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct RawBmpMut<'a> {
+    /// Image header.
+    header: Header,
+
+    /// Color type.
+    pub(crate) color_type: ColorType,
+
+    /// Color table for color mapped images.
+    color_table: Option<ColorTable<'a>>,
+
+    /// Image data.
+    image_data: &'a mut [u8],
+
+    /// Embedded or linked ICC profile.
+    icc_profile: Option<IccProfile<'a>>,
+}
+
+impl<'a> RawBmpMut<'a> {
+    /// Create a mutable bitmap object from a byte slice.
+    ///
+    /// Returns [`ParseError::UnsupportedCompressionMethod`] if the image is RLE compressed or
+    /// contains an embedded JPEG or PNG image and [`ParseError::UnsupportedBpp`] for 64 bpp images.
+    pub fn from_slice(bytes: &'a mut [u8]) -> Result<Self, ParseError> {
+        let raw_bmp = RawBmp::from_slice(bytes)?;
+        let header = *raw_bmp.header();
+        let color_type = raw_bmp.color_type;
+        let image_data_len = raw_bmp.image_data().len();
+
+        if matches!(
+            header.compression_method,
+            CompressionMethod::Rle8
+                | CompressionMethod::Rle4
+                | CompressionMethod::Jpeg
+                | CompressionMethod::Png
+        ) {
+            return Err(ParseError::UnsupportedCompressionMethod(
+                header.compression_method.value(),
+            ));
+        }
+
+        // Raw pixel values are passed as `u32`, which can't hold 64 bpp pixels.
+        if header.bpp == Bpp::Bits64 {
+            return Err(ParseError::UnsupportedBpp(64));
+        }
+
+        let (header_data, image_data) = bytes.split_at_mut(header.image_data_start);
+        let (image_data, trailing_data) = image_data.split_at_mut(image_data_len);
+        let header_data: &'a [u8] = header_data;
+        let trailing_data: &'a [u8] = trailing_data;
+        let (_, (_, color_table)) = Header::parse(header_data)?;
+
+        // The ICC profile can be stored before or after the image data, but must not overlap it.
+        let icc_profile = header.color_space.and_then(|color_space| {
+            let offset = FILE_HEADER_LEN.saturating_add(color_space.profile_offset as usize);
+            let image_data_end = header.image_data_start + image_data_len;
+            match offset.checked_sub(image_data_end) {
+                Some(offset) => IccProfile::parse(trailing_data, offset, &color_space),
+                None => IccProfile::parse(header_data, offset, &color_space),
+            }
+        });
+
+        Ok(Self {
+            header,
+            color_type,
+            color_table,
+            image_data,
+            icc_profile,
+        })
+    }
+
+    /// Returns a shared view of the image.
+    ///
+    /// The returned [`RawBmp`] can be used to read pixels and to iterate over the image data.
+    pub fn as_raw(&self) -> RawBmp<'_> {
+        RawBmp::from_parts(
+            self.header,
+            self.color_type,
+            self.color_table,
+            self.image_data,
+            self.icc_profile,
+        )
+    }
+
+    /// Returns the color table associated with the image.
+    pub fn color_table(&self) -> Option<&ColorTable<'a>> {
+        self.color_table.as_ref()
+    }
+
+    /// Returns a slice containing the raw image data.
+    pub fn image_data(&self) -> &[u8] {
+        self.image_data
+    }
+
+    /// Returns a mutable slice containing the raw image data.
+    pub fn image_data_mut(&mut self) -> &mut [u8] {
+        self.image_data
+    }
+
+    /// Returns a reference to the BMP header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the raw color of a pixel.
+    ///
+    /// See [`RawBmp::pixel`] for more information.
+    pub fn pixel(&self, p: Point) -> Option<u32> {
+        self.as_raw().pixel(p)
+    }
+
+    /// Sets the raw color of a pixel.
+    ///
+    /// Pixels outside the image bounding box are ignored. For indexed images `raw` is the color
+    /// table index. Bits which exceed the bit depth of the image are ignored.
+    pub fn set_pixel(&mut self, p: Point, raw: u32) {
+        write_raw_pixel(self.image_data, &self.header, p, raw);
+    }
+}
+
+/// Writes a raw pixel value into uncompressed image data.
+///
+/// Pixels outside the image are ignored.
+pub(crate) fn write_raw_pixel(image_data: &mut [u8], header: &Header, p: Point, raw: u32) {
+    let size = header.image_size;
+    if p.x < 0 || p.y < 0 || p.x as u32 >= size.width || p.y as u32 >= size.height {
+        return;
+    }
+
+    let row = match header.row_order {
+        RowOrder::TopDown => p.y as usize,
+        RowOrder::BottomUp => (size.height - 1) as usize - p.y as usize,
+    };
+    let bpp = usize::from(header.bpp.bits());
+    let bit_offset = p.x as usize * bpp;
+    let offset = row * header.bytes_per_row() + bit_offset / 8;
+
+    if bpp < 8 {
+        let shift = 8 - bpp - bit_offset % 8;
+        let mask = ((1u16 << bpp) - 1) as u8;
+        if let Some(byte) = image_data.get_mut(offset) {
+            *byte = (*byte & !(mask << shift)) | (((raw as u8) & mask) << shift);
+        }
+    } else if let Some(bytes) = image_data.get_mut(offset..offset + bpp / 8) {
+        bytes.copy_from_slice(&raw.to_le_bytes()[..bpp / 8]);
+    }
+}
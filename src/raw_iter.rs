@@ -1,14 +1,16 @@
-use core::{iter, slice};
+use core::{iter, ops::Range, slice};
 
 use embedded_graphics::{
     iterator::raw::RawDataSlice,
-    pixelcolor::raw::{LittleEndian, RawU1, RawU16, RawU24, RawU32, RawU4, RawU8},
+    pixelcolor::raw::{LittleEndian, RawU1, RawU16, RawU2, RawU24, RawU32, RawU4, RawU8},
     prelude::*,
+    primitives::Rectangle,
 };
 
 use crate::{
     header::{Bpp, CompressionMethod, RowOrder},
     raw_bmp::RawBmp,
+    scrgb,
 };
 
 /// Iterator over raw pixel colors.
@@ -20,6 +22,8 @@ where
     rows: slice::ChunksExact<'a, u8>,
     row_order: RowOrder,
     current_row: iter::Take<<RawDataSlice<'a, R, LittleEndian> as IntoIterator>::IntoIter>,
+    /// Index of the first returned value in each row.
+    x: usize,
     width: usize,
 }
 
@@ -29,15 +33,53 @@ where
 {
     /// Create a new raw color iterator.
     pub(crate) fn new(raw_bmp: &'a RawBmp<'a>) -> Self {
+        let size = raw_bmp.header().image_size;
+
+        Self::with_columns(raw_bmp, 0..size.width as usize, 0..size.height as usize)
+    }
+
+    /// Creates a new raw color iterator for a part of the image.
+    ///
+    /// `area` must be inside the image bounding box.
+    pub(crate) fn with_area(raw_bmp: &'a RawBmp<'a>, area: &Rectangle) -> Self {
+        let (columns, rows) = area_ranges(area);
+
+        Self::with_columns(raw_bmp, columns, rows)
+    }
+
+    /// Creates a new raw color iterator which returns the values in the `columns` range of each
+    /// row in the `rows` range.
+    ///
+    /// Rows are counted from the top of the image, regardless of the row order.
+    fn with_columns(raw_bmp: &'a RawBmp<'a>, columns: Range<usize>, rows: Range<usize>) -> Self {
         let header = raw_bmp.header();
 
-        let width = header.image_size.width as usize;
+        // Embedded JPEG and PNG images can't be accessed as raw pixels.
+        let image_data = if header.compression_method.is_embedded_image() {
+            &[]
+        } else {
+            raw_bmp.image_data()
+        };
+
+        // Only the rows inside the area are iterated over, which skips the data before and after
+        // these rows without reading it.
+        let height = header.image_size.height as usize;
+        let stored_rows = match header.row_order {
+            RowOrder::TopDown => rows,
+            RowOrder::BottomUp => {
+                height.saturating_sub(rows.end)..height.saturating_sub(rows.start)
+            }
+        };
+        let bytes_per_row = header.bytes_per_row();
+        let start = (stored_rows.start * bytes_per_row).min(image_data.len());
+        let end = (stored_rows.end * bytes_per_row).min(image_data.len());
 
         Self {
-            rows: raw_bmp.image_data().chunks_exact(header.bytes_per_row()),
-            row_order: raw_bmp.header().row_order,
+            rows: image_data[start..end].chunks_exact(bytes_per_row),
+            row_order: header.row_order,
             current_row: RawDataSlice::new(&[]).into_iter().take(0),
-            width,
+            x: columns.start,
+            width: columns.len(),
         }
     }
 }
@@ -55,19 +97,152 @@ where
                 RowOrder::BottomUp => self.rows.next_back(),
             }?;
 
-            self.current_row = RawDataSlice::new(next_row).into_iter().take(self.width);
+            let mut row = RawDataSlice::new(next_row).into_iter();
+            if self.x > 0 {
+                // `nth` is `O(1)` for raw data iterators.
+                row.nth(self.x - 1);
+            }
+            self.current_row = row.take(self.width);
 
             self.current_row.next()
         })
     }
 }
 
+/// Returns the column and row ranges covered by an area.
+fn area_ranges(area: &Rectangle) -> (Range<usize>, Range<usize>) {
+    let x = area.top_left.x as usize;
+    let y = area.top_left.y as usize;
+
+    (
+        x..x + area.size.width as usize,
+        y..y + area.size.height as usize,
+    )
+}
+
+/// Iterator over the raw pixel colors in a 64 bpp image.
+///
+/// Each pixel is returned as a `u64` value, which contains the blue, green, red and alpha channels
+/// in order of increasing significance. See [`RawBmp::pixel_wide`] for more information about the
+/// channel format.
+#[allow(missing_debug_implementations)]
+pub struct Raw64Colors<'a> {
+    /// The 64 bit values are read as pairs of 32 bit values.
+    colors: RawColors<'a, RawU32>,
+}
+
+impl<'a> Raw64Colors<'a> {
+    /// Create a new raw color iterator.
+    pub(crate) fn new(raw_bmp: &'a RawBmp<'a>) -> Self {
+        Self::with_area(
+            raw_bmp,
+            &Rectangle::new(Point::zero(), raw_bmp.header().image_size),
+        )
+    }
+
+    /// Creates a new raw color iterator for a part of the image.
+    ///
+    /// `area` must be inside the image bounding box.
+    pub(crate) fn with_area(raw_bmp: &'a RawBmp<'a>, area: &Rectangle) -> Self {
+        let (columns, rows) = area_ranges(area);
+
+        Self {
+            colors: RawColors::with_columns(raw_bmp, columns.start * 2..columns.end * 2, rows),
+        }
+    }
+}
+
+impl Iterator for Raw64Colors<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let low = self.colors.next()?.into_inner();
+        let high = self.colors.next()?.into_inner();
+
+        Some(u64::from(high) << 32 | u64::from(low))
+    }
+}
+
+/// Iterator over the rows in an uncompressed image.
+///
+/// See [`RawBmp::rows`] for more information.
+#[derive(Clone, Debug)]
+pub struct RawRows<'a> {
+    rows: slice::ChunksExact<'a, u8>,
+    row_order: RowOrder,
+    /// Length of a row without padding.
+    row_len: usize,
+    /// Index of the next returned row.
+    index: u32,
+}
+
+impl<'a> RawRows<'a> {
+    /// Creates a new row iterator.
+    pub(crate) fn new(raw_bmp: &RawBmp<'a>) -> Self {
+        let header = raw_bmp.header();
+
+        // Compressed images don't contain rows of raw pixels.
+        let image_data = match header.compression_method {
+            CompressionMethod::Rgb
+            | CompressionMethod::Bitfields
+            | CompressionMethod::AlphaBitfields => raw_bmp.image_data(),
+            _ => &[],
+        };
+
+        let bits_per_row = header.image_size.width as usize * header.bpp.bits() as usize;
+
+        Self {
+            rows: image_data.chunks_exact(header.bytes_per_row()),
+            row_order: header.row_order,
+            row_len: bits_per_row.div_ceil(8),
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for RawRows<'a> {
+    type Item = RawRow<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = match self.row_order {
+            RowOrder::TopDown => self.rows.next(),
+            RowOrder::BottomUp => self.rows.next_back(),
+        }?;
+
+        let index = self.index;
+        self.index += 1;
+
+        Some(RawRow {
+            index,
+            data: &row[..self.row_len],
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl ExactSizeIterator for RawRows<'_> {}
+
+/// Row of raw image data.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub struct RawRow<'a> {
+    /// The row index, counted from the top of the image.
+    pub index: u32,
+
+    /// The raw pixel data of the row without padding bytes.
+    pub data: &'a [u8],
+}
+
 /// Iterator over the raw colors in the image.
 ///
 /// See [`RawBmp::colors`](RawBmp::colors) for more information.
 pub enum DynamicRawColors<'a> {
     /// 1 bit per pixel
     Bpp1(RawColors<'a, RawU1>),
+    /// 2 bits per pixel
+    Bpp2(RawColors<'a, RawU2>),
     /// 4 bits per pixel
     Bpp4(RawColors<'a, RawU4>),
     /// 8 bits per pixel
@@ -78,6 +253,11 @@ pub enum DynamicRawColors<'a> {
     Bpp24(RawColors<'a, RawU24>),
     /// 32 bits per pixel
     Bpp32(RawColors<'a, RawU32>),
+    /// 64 bits per pixel
+    ///
+    /// [`DynamicRawColors`] returns the colors converted into `ARGB8888` values. The wrapped
+    /// iterator can be used to access the unconverted 64 bit values.
+    Bpp64(Raw64Colors<'a>),
     /// RLE encoded with 4 bits per pixel
     Bpp4Rle(Rle4Colors<'a>),
     /// RLE encoded with 8 bits per pixel
@@ -88,11 +268,13 @@ impl core::fmt::Debug for DynamicRawColors<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             DynamicRawColors::Bpp1(_) => f.debug_tuple("DynamicRawColors::Bpp1").finish(),
+            DynamicRawColors::Bpp2(_) => f.debug_tuple("DynamicRawColors::Bpp2").finish(),
             DynamicRawColors::Bpp4(_) => f.debug_tuple("DynamicRawColors::Bpp4").finish(),
             DynamicRawColors::Bpp8(_) => f.debug_tuple("DynamicRawColors::Bpp8").finish(),
             DynamicRawColors::Bpp16(_) => f.debug_tuple("DynamicRawColors::Bpp16").finish(),
             DynamicRawColors::Bpp24(_) => f.debug_tuple("DynamicRawColors::Bpp24").finish(),
             DynamicRawColors::Bpp32(_) => f.debug_tuple("DynamicRawColors::Bpp32").finish(),
+            DynamicRawColors::Bpp64(_) => f.debug_tuple("DynamicRawColors::Bpp64").finish(),
             DynamicRawColors::Bpp4Rle(_) => f.debug_tuple("DynamicRawColors::Bpp4Rle").finish(),
             DynamicRawColors::Bpp8Rle(_) => f.debug_tuple("DynamicRawColors::Bpp8Rle").finish(),
         }
@@ -104,11 +286,13 @@ impl DynamicRawColors<'_> {
     pub fn row_order(&self) -> RowOrder {
         match self {
             DynamicRawColors::Bpp1(colors) => colors.row_order,
+            DynamicRawColors::Bpp2(colors) => colors.row_order,
             DynamicRawColors::Bpp4(colors) => colors.row_order,
             DynamicRawColors::Bpp8(colors) => colors.row_order,
             DynamicRawColors::Bpp16(colors) => colors.row_order,
             DynamicRawColors::Bpp24(colors) => colors.row_order,
             DynamicRawColors::Bpp32(colors) => colors.row_order,
+            DynamicRawColors::Bpp64(colors) => colors.colors.row_order,
             DynamicRawColors::Bpp4Rle(_) => RowOrder::BottomUp,
             DynamicRawColors::Bpp8Rle(_) => RowOrder::BottomUp,
         }
@@ -121,17 +305,35 @@ impl Iterator for DynamicRawColors<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             DynamicRawColors::Bpp1(colors) => colors.next().map(|r| u32::from(r.into_inner())),
+            DynamicRawColors::Bpp2(colors) => colors.next().map(|r| u32::from(r.into_inner())),
             DynamicRawColors::Bpp4(colors) => colors.next().map(|r| u32::from(r.into_inner())),
             DynamicRawColors::Bpp8(colors) => colors.next().map(|r| u32::from(r.into_inner())),
             DynamicRawColors::Bpp16(colors) => colors.next().map(|r| u32::from(r.into_inner())),
             DynamicRawColors::Bpp24(colors) => colors.next().map(|r| r.into_inner()),
             DynamicRawColors::Bpp32(colors) => colors.next().map(|r| r.into_inner()),
-            DynamicRawColors::Bpp4Rle(colors) => colors.next().map(|r| u32::from(r.into_inner())),
-            DynamicRawColors::Bpp8Rle(colors) => colors.next().map(|r| u32::from(r.into_inner())),
+            DynamicRawColors::Bpp64(colors) => colors.next().map(scrgb::to_argb8888),
+            DynamicRawColors::Bpp4Rle(colors) => colors
+                .next()
+                .map(|r| r.map_or(0, |r| u32::from(r.into_inner()))),
+            DynamicRawColors::Bpp8Rle(colors) => colors
+                .next()
+                .map(|r| r.map_or(0, |r| u32::from(r.into_inner()))),
         }
     }
 }
 
+/// RLE decoder which can start decoding at the beginning of a row.
+///
+/// Decoding can only start at positions where the start of a row coincides with the start of an
+/// RLE command. These positions are recorded in RLE row indices.
+pub(crate) trait RleRows<'a>: Iterator + Sized {
+    /// Creates a decoder which starts at the given row and image data offset.
+    fn at_row_start(raw_bmp: &RawBmp<'a>, row: u32, offset: usize) -> Self;
+
+    /// Returns the current row and image data offset, if the next command starts a new row.
+    fn row_start(&self) -> Option<(u32, usize)>;
+}
+
 /// The state for our RLE* decoder
 #[derive(Debug)]
 enum RleState {
@@ -153,6 +355,193 @@ enum RleState {
     EndOfBitmap,
 }
 
+impl RleState {
+    /// Skips up to `n` pixels without decoding them individually.
+    ///
+    /// Undefined pixels and the pixels in an encoded run are skipped at once. Returns the number
+    /// of skipped pixels, which is `0` if the next pixel needs to be decoded to move forward, or
+    /// `None` if the end of the image was reached.
+    fn skip(&mut self, cursor: &mut RleCursor, n: usize) -> Option<usize> {
+        let skipped = if cursor.skip > 0 {
+            let skipped = n.min(cursor.skip);
+            cursor.skip -= skipped;
+            skipped
+        } else if let RleState::Running {
+            remaining,
+            value,
+            is_odd,
+        } = *self
+        {
+            cursor.line_start = false;
+            let skipped = n.min(usize::from(remaining) + 1);
+            *self = if skipped > usize::from(remaining) {
+                RleState::Starting
+            } else {
+                RleState::Running {
+                    remaining: remaining - skipped as u8,
+                    value,
+                    is_odd,
+                }
+            };
+            skipped
+        } else {
+            return Some(0);
+        };
+
+        (cursor.advance_by(skipped) == skipped).then_some(skipped)
+    }
+}
+
+/// Position of the RLE decoder in the image.
+#[derive(Debug)]
+struct RleCursor {
+    /// Image width.
+    width: u32,
+    /// Image height.
+    height: u32,
+    /// Number of pixels in the current row, which were already returned.
+    ///
+    /// A value equal to `width` indicates that the row is complete. The decoder only moves to
+    /// the next row when the next pixel is returned, which makes sure that an end of line marker
+    /// after a complete row doesn't skip the following row.
+    x: u32,
+    /// Current row, counted from the start of the image data.
+    row: u32,
+    /// Number of undefined pixels before the next decoded pixel.
+    skip: usize,
+    /// `true` if no pixels were decoded since the cursor was moved to the start of a row.
+    ///
+    /// An end of line marker at such a position skips an entire row, while an end of line marker
+    /// after a row which was completed by decoded pixels doesn't skip any pixels.
+    line_start: bool,
+}
+
+impl RleCursor {
+    fn new(raw_bmp: &RawBmp<'_>) -> Self {
+        let size = raw_bmp.header().image_size;
+
+        Self {
+            width: size.width,
+            height: size.height,
+            x: 0,
+            row: 0,
+            skip: 0,
+            line_start: true,
+        }
+    }
+
+    /// Creates a cursor which is positioned at the start of the given row.
+    fn at_row(raw_bmp: &RawBmp<'_>, row: u32) -> Self {
+        let mut cursor = Self::new(raw_bmp);
+        if row > 0 {
+            // Use the same position a decoder has after an end of line marker at the end of the
+            // previous row.
+            cursor.x = cursor.width;
+            cursor.row = row - 1;
+        }
+
+        cursor
+    }
+
+    /// Returns the row which starts at the current cursor position and the offset of the first
+    /// command of this row relative to `data`.
+    ///
+    /// `data` is the remaining image data. Returns `None` if the cursor isn't positioned at the
+    /// start of a row or if pixels still need to be skipped.
+    fn row_start(&self, data: &[u8]) -> Option<(u32, usize)> {
+        if self.skip != 0 {
+            None
+        } else if self.x == 0 && self.row == 0 {
+            Some((0, 0))
+        } else if self.x == self.width {
+            // An end of line marker after a row which was completed by decoded pixels belongs to
+            // that row. A decoder which starts at the next row would skip an entire row instead.
+            let offset = match data {
+                _ if self.line_start => 0,
+                [0, 0, ..] => 2,
+                [_, _, ..] => 0,
+                // The next command isn't known yet.
+                _ => return None,
+            };
+
+            Some((self.row + 1, offset))
+        } else {
+            None
+        }
+    }
+
+    /// Moves the cursor to the next decoded pixel.
+    ///
+    /// Returns `false` if the end of the image was reached.
+    fn advance(&mut self) -> bool {
+        self.line_start = false;
+
+        if self.x >= self.width {
+            self.x = 0;
+            self.row += 1;
+        }
+        if self.row >= self.height {
+            return false;
+        }
+
+        self.x += 1;
+        true
+    }
+
+    /// Moves the cursor up to `n` pixels forward.
+    ///
+    /// Returns the number of pixels the cursor was moved, which is less than `n` if the end of the
+    /// image was reached.
+    fn advance_by(&mut self, n: usize) -> usize {
+        let mut remaining = n;
+        while remaining > 0 {
+            if self.x >= self.width {
+                self.x = 0;
+                self.row += 1;
+            }
+            if self.row >= self.height {
+                break;
+            }
+
+            let step = remaining.min((self.width - self.x) as usize);
+            self.x += step as u32;
+            remaining -= step;
+        }
+
+        n - remaining
+    }
+
+    /// Skips the remaining pixels in the current row.
+    ///
+    /// If the cursor is already at the start of a row, the entire row is skipped.
+    fn end_of_line(&mut self) {
+        self.skip = if self.line_start {
+            self.width as usize
+        } else {
+            self.width.saturating_sub(self.x) as usize
+        };
+        self.line_start = true;
+    }
+
+    /// Skips pixels to move the cursor `dx` pixels to the right and `dy` rows up.
+    fn delta(&mut self, dx: u8, dy: u8) {
+        self.skip = usize::from(dy) * self.width as usize + usize::from(dx);
+        self.line_start &= dx == 0;
+    }
+
+    /// Returns the next skipped pixel.
+    ///
+    /// Returns `None` if no pixels need to be skipped.
+    fn next_skipped<T>(&mut self) -> Option<Option<Option<T>>> {
+        if self.skip == 0 {
+            return None;
+        }
+
+        self.skip -= 1;
+        Some((self.advance_by(1) == 1).then_some(None))
+    }
+}
+
 pub struct PixelPoints {
     /// The location of the next pixel.
     next_pixel: Point,
@@ -195,14 +584,20 @@ impl Iterator for PixelPoints {
 
 /// Iterator over individual BMP RLE8 encoded pixels.
 ///
-/// Each pixel is returned as a `u32` regardless of the bit depth of the source image.
+/// The pixels are returned in bottom-up order, starting at the bottom left corner of the image.
+/// Pixels which are skipped by a delta escape or by an end of line marker are undefined and are
+/// returned as `None`. The iterator ends at the end of bitmap marker or after the last pixel in
+/// the image.
 #[derive(Debug)]
 pub struct Rle8Colors<'a> {
     /// Our source data
     data: &'a [u8],
     /// Our state
     rle_state: RleState,
-    start_of_row: bool,
+    /// Position in the image
+    cursor: RleCursor,
+    /// Length of the image data
+    image_data_len: usize,
 }
 
 impl<'a> Rle8Colors<'a> {
@@ -211,21 +606,49 @@ impl<'a> Rle8Colors<'a> {
         Rle8Colors {
             data: raw_bmp.image_data(),
             rle_state: RleState::Starting,
-            start_of_row: false,
+            cursor: RleCursor::new(raw_bmp),
+            image_data_len: raw_bmp.image_data().len(),
         }
     }
 
-    /// Indicate that a new line is starting. Required for correct RLE decoding.
-    pub fn start_row(&mut self) {
-        self.start_of_row = true;
+    /// Indicate that a new line is starting.
+    ///
+    /// This method doesn't do anything, because the decoder keeps track of the position in the
+    /// image itself.
+    #[deprecated(note = "the decoder keeps track of rows itself")]
+    pub fn start_row(&mut self) {}
+}
+
+impl<'a> RleRows<'a> for Rle8Colors<'a> {
+    fn at_row_start(raw_bmp: &RawBmp<'a>, row: u32, offset: usize) -> Self {
+        Self {
+            data: raw_bmp.image_data().get(offset..).unwrap_or_default(),
+            rle_state: RleState::Starting,
+            cursor: RleCursor::at_row(raw_bmp, row),
+            image_data_len: raw_bmp.image_data().len(),
+        }
+    }
+
+    fn row_start(&self) -> Option<(u32, usize)> {
+        if !matches!(self.rle_state, RleState::Starting) {
+            return None;
+        }
+
+        self.cursor
+            .row_start(self.data)
+            .map(|(row, offset)| (row, self.image_data_len - self.data.len() + offset))
     }
 }
 
 impl<'a> Iterator for Rle8Colors<'a> {
-    type Item = RawU8;
+    type Item = Option<RawU8>;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
+            if let Some(pixel) = self.cursor.next_skipped() {
+                return pixel;
+            }
+
             match self.rle_state {
                 RleState::EndOfBitmap => {
                     return None;
@@ -250,7 +673,7 @@ impl<'a> Iterator for Rle8Colors<'a> {
                     } else {
                         self.data = self.data.get(1..)?;
                     }
-                    return Some(RawU8::from(value));
+                    return self.cursor.advance().then_some(Some(RawU8::from(value)));
                 }
                 RleState::Running {
                     remaining,
@@ -266,12 +689,12 @@ impl<'a> Iterator for Rle8Colors<'a> {
                             is_odd,
                         };
                     }
-                    return Some(RawU8::from(value));
+                    return self.cursor.advance().then_some(Some(RawU8::from(value)));
                 }
                 RleState::Starting => {
-                    let length = *self.data.get(0)?;
+                    let length = *self.data.first()?;
                     let param = *self.data.get(1)?;
-                    self.data = &self.data.get(2..)?;
+                    self.data = self.data.get(2..)?;
                     match length {
                         0 => {
                             // The first byte of the pair can be set to zero to
@@ -282,17 +705,19 @@ impl<'a> Iterator for Rle8Colors<'a> {
                             // the pair, which can be one of the following values.
                             match param {
                                 0 => {
-                                    if !self.start_of_row {
-                                        return None;
-                                    }
+                                    // End of line
+                                    self.cursor.end_of_line();
                                 }
                                 1 => {
                                     // End of bitmap
                                     self.rle_state = RleState::EndOfBitmap;
                                 }
                                 2 => {
-                                    // Delta encoding is unsupported.
-                                    return None;
+                                    // Delta
+                                    let dx = *self.data.first()?;
+                                    let dy = *self.data.get(1)?;
+                                    self.data = self.data.get(2..)?;
+                                    self.cursor.delta(dx, dy);
                                 }
                                 _ => {
                                     // Absolute mode
@@ -318,18 +743,39 @@ impl<'a> Iterator for Rle8Colors<'a> {
             }
         }
     }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        // Skip runs and undefined pixels without decoding each pixel.
+        while n > 0 {
+            match self.rle_state.skip(&mut self.cursor, n)? {
+                0 => {
+                    self.next()?;
+                    n -= 1;
+                }
+                skipped => n -= skipped,
+            }
+        }
+
+        self.next()
+    }
 }
 
 /// Iterator over individual BMP RLE4 encoded pixels.
 ///
-/// Each pixel is returned as a `u32` regardless of the bit depth of the source image.
+/// The pixels are returned in bottom-up order, starting at the bottom left corner of the image.
+/// Pixels which are skipped by a delta escape or by an end of line marker are undefined and are
+/// returned as `None`. The iterator ends at the end of bitmap marker or after the last pixel in
+/// the image.
 #[derive(Debug)]
 pub struct Rle4Colors<'a> {
     /// Our source data
     data: &'a [u8],
     /// Our state
     rle_state: RleState,
-    start_of_row: bool,
+    /// Position in the image
+    cursor: RleCursor,
+    /// Length of the image data
+    image_data_len: usize,
 }
 
 impl<'a> Rle4Colors<'a> {
@@ -338,21 +784,49 @@ impl<'a> Rle4Colors<'a> {
         Rle4Colors {
             data: raw_bmp.image_data(),
             rle_state: RleState::Starting,
-            start_of_row: false,
+            cursor: RleCursor::new(raw_bmp),
+            image_data_len: raw_bmp.image_data().len(),
         }
     }
 
-    /// Indicate that a new line is starting. Required for correct RLE decoding.
-    pub fn start_row(&mut self) {
-        self.start_of_row = true;
+    /// Indicate that a new line is starting.
+    ///
+    /// This method doesn't do anything, because the decoder keeps track of the position in the
+    /// image itself.
+    #[deprecated(note = "the decoder keeps track of rows itself")]
+    pub fn start_row(&mut self) {}
+}
+
+impl<'a> RleRows<'a> for Rle4Colors<'a> {
+    fn at_row_start(raw_bmp: &RawBmp<'a>, row: u32, offset: usize) -> Self {
+        Self {
+            data: raw_bmp.image_data().get(offset..).unwrap_or_default(),
+            rle_state: RleState::Starting,
+            cursor: RleCursor::at_row(raw_bmp, row),
+            image_data_len: raw_bmp.image_data().len(),
+        }
+    }
+
+    fn row_start(&self) -> Option<(u32, usize)> {
+        if !matches!(self.rle_state, RleState::Starting) {
+            return None;
+        }
+
+        self.cursor
+            .row_start(self.data)
+            .map(|(row, offset)| (row, self.image_data_len - self.data.len() + offset))
     }
 }
 
 impl<'a> Iterator for Rle4Colors<'a> {
-    type Item = RawU4;
+    type Item = Option<RawU4>;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
+            if let Some(pixel) = self.cursor.next_skipped() {
+                return pixel;
+            }
+
             match self.rle_state {
                 RleState::EndOfBitmap => {
                     return None;
@@ -398,7 +872,10 @@ impl<'a> Iterator for Rle4Colors<'a> {
                         // remove the padding byte too
                         self.data = self.data.get(1..)?;
                     }
-                    return Some(RawU4::from(nibble_value));
+                    return self
+                        .cursor
+                        .advance()
+                        .then_some(Some(RawU4::from(nibble_value)));
                 }
                 RleState::Running {
                     remaining,
@@ -432,12 +909,15 @@ impl<'a> Iterator for Rle4Colors<'a> {
                         };
                     }
 
-                    return Some(RawU4::from(nibble_value));
+                    return self
+                        .cursor
+                        .advance()
+                        .then_some(Some(RawU4::from(nibble_value)));
                 }
                 RleState::Starting => {
-                    let length = *self.data.get(0)?;
+                    let length = *self.data.first()?;
                     let param = *self.data.get(1)?;
-                    self.data = &self.data.get(2..)?;
+                    self.data = self.data.get(2..)?;
                     match length {
                         0 => {
                             // The first byte of the pair can be set to zero to
@@ -448,17 +928,19 @@ impl<'a> Iterator for Rle4Colors<'a> {
                             // the pair, which can be one of the following values.
                             match param {
                                 0 => {
-                                    if !self.start_of_row {
-                                        return None;
-                                    }
+                                    // End of line
+                                    self.cursor.end_of_line();
                                 }
                                 1 => {
                                     // End of bitmap
                                     self.rle_state = RleState::EndOfBitmap;
                                 }
                                 2 => {
-                                    // Delta encoding is unsupported.
-                                    return None;
+                                    // Delta
+                                    let dx = *self.data.first()?;
+                                    let dy = *self.data.get(1)?;
+                                    self.data = self.data.get(2..)?;
+                                    self.cursor.delta(dx, dy);
                                 }
                                 num_pixels => {
                                     let num_bytes = num_pixels.div_ceil(2);
@@ -485,6 +967,21 @@ impl<'a> Iterator for Rle4Colors<'a> {
             }
         }
     }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        // Skip runs and undefined pixels without decoding each pixel.
+        while n > 0 {
+            match self.rle_state.skip(&mut self.cursor, n)? {
+                0 => {
+                    self.next()?;
+                    n -= 1;
+                }
+                skipped => n -= skipped,
+            }
+        }
+
+        self.next()
+    }
 }
 
 /// Iterator over individual BMP pixels.
@@ -516,15 +1013,22 @@ impl<'a> RawPixels<'a> {
                     points,
                 }
             }
-            CompressionMethod::Rgb | CompressionMethod::Bitfields => {
-                let points = PixelPoints::new(header.image_size, header.row_order);
+            CompressionMethod::Rgb
+            | CompressionMethod::Bitfields
+            | CompressionMethod::AlphaBitfields
+            | CompressionMethod::Jpeg
+            | CompressionMethod::Png => {
+                // `RawColors` returns the rows in top-down order, regardless of the row order.
+                let points = PixelPoints::new(header.image_size, RowOrder::TopDown);
                 let colors = match header.bpp {
                     Bpp::Bits1 => DynamicRawColors::Bpp1(RawColors::new(raw_bmp)),
+                    Bpp::Bits2 => DynamicRawColors::Bpp2(RawColors::new(raw_bmp)),
                     Bpp::Bits4 => DynamicRawColors::Bpp4(RawColors::new(raw_bmp)),
                     Bpp::Bits8 => DynamicRawColors::Bpp8(RawColors::new(raw_bmp)),
                     Bpp::Bits16 => DynamicRawColors::Bpp16(RawColors::new(raw_bmp)),
                     Bpp::Bits24 => DynamicRawColors::Bpp24(RawColors::new(raw_bmp)),
                     Bpp::Bits32 => DynamicRawColors::Bpp32(RawColors::new(raw_bmp)),
+                    Bpp::Bits64 => DynamicRawColors::Bpp64(Raw64Colors::new(raw_bmp)),
                 };
                 Self { colors, points }
             }
@@ -536,9 +1040,23 @@ impl Iterator for RawPixels<'_> {
     type Item = RawPixel;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let color = self.colors.next()?;
-        let position = self.points.next()?;
-        Some(RawPixel { position, color })
+        loop {
+            // Undefined pixels in RLE compressed images are skipped.
+            let color = match &mut self.colors {
+                DynamicRawColors::Bpp4Rle(colors) => {
+                    colors.next()?.map(|raw| u32::from(raw.into_inner()))
+                }
+                DynamicRawColors::Bpp8Rle(colors) => {
+                    colors.next()?.map(|raw| u32::from(raw.into_inner()))
+                }
+                colors => Some(colors.next()?),
+            };
+            let position = self.points.next()?;
+
+            if let Some(color) = color {
+                return Some(RawPixel { position, color });
+            }
+        }
     }
 }
 
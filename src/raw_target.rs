@@ -0,0 +1,94 @@
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+/// Raw pixel format.
+///
+/// The raw pixel format describes the memory layout of the pixel data which is accepted by a
+/// [`RawDrawTarget`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum RawFormat {
+    /// 16 bit `Rgb555` pixels in little endian byte order.
+    Rgb555Le,
+
+    /// 16 bit `Rgb555` pixels in big endian byte order.
+    Rgb555Be,
+
+    /// 16 bit `Rgb565` pixels in little endian byte order.
+    Rgb565Le,
+
+    /// 16 bit `Rgb565` pixels in big endian byte order.
+    Rgb565Be,
+
+    /// 24 bit `Rgb888` pixels in little endian byte order.
+    ///
+    /// The bytes of each pixel are stored in blue, green, red order.
+    Rgb888Le,
+
+    /// 24 bit `Rgb888` pixels in big endian byte order.
+    ///
+    /// The bytes of each pixel are stored in red, green, blue order.
+    Rgb888Be,
+}
+
+/// Draw target which accepts raw pixel data.
+///
+/// Display drivers can implement this trait to receive the pixel data of BMP images without
+/// color conversion, see [`Bmp::draw_raw`](crate::Bmp::draw_raw). This makes it possible to
+/// transfer the image data directly to the display, for example by using DMA.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+/// use tinybmp::{Bmp, RawDrawTarget, RawFormat};
+///
+/// struct Display {
+///     // ...
+/// }
+///
+/// impl RawDrawTarget for Display {
+///     const RAW_FORMAT: RawFormat = RawFormat::Rgb565Le;
+///
+///     fn fill_contiguous_raw(&mut self, area: &Rectangle, data: &[u8]) -> Result<(), Self::Error> {
+///         // Set the address window of the display to `area` and transfer `data`.
+///         Ok(())
+///     }
+/// }
+///
+/// # impl DrawTarget for Display {
+/// #     type Color = Rgb565;
+/// #     type Error = core::convert::Infallible;
+/// #
+/// #     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+/// #     where
+/// #         I: IntoIterator<Item = Pixel<Self::Color>>,
+/// #     {
+/// #         Ok(())
+/// #     }
+/// # }
+/// #
+/// # impl OriginDimensions for Display {
+/// #     fn size(&self) -> Size {
+/// #         Size::new(320, 240)
+/// #     }
+/// # }
+/// #
+/// # let mut display = Display {};
+/// # let data = include_bytes!("../tests/chessboard-4px-color-16bit.bmp");
+/// let bmp = Bmp::<Rgb565>::from_slice(data).unwrap();
+///
+/// // The pixel data of 16 bpp RGB565 images is transferred without color conversion.
+/// bmp.draw_raw(&mut display, Point::new(10, 20))?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+pub trait RawDrawTarget: DrawTarget {
+    /// The raw pixel format which is accepted by
+    /// [`fill_contiguous_raw`](Self::fill_contiguous_raw).
+    const RAW_FORMAT: RawFormat;
+
+    /// Fills an area with raw pixel data.
+    ///
+    /// `data` contains the pixels inside `area` in [`RAW_FORMAT`](Self::RAW_FORMAT). The pixels
+    /// are stored row by row, starting at the top left corner of the area, without any padding
+    /// between the rows.
+    fn fill_contiguous_raw(&mut self, area: &Rectangle, data: &[u8]) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,103 @@
+//! Conversion of 64 bpp scRGB pixels.
+//!
+//! 64 bpp BMP files store the blue, green, red and alpha channels as 16 bit signed fixed point
+//! values with 13 fractional bits (s2.13). The color channels use linear gamma.
+
+/// Fixed point value for `1.0`.
+const ONE: i16 = 1 << 13;
+
+/// Thresholds for the conversion of linear s2.13 values into 8 bit sRGB values.
+///
+/// Entry `n` is the smallest linear value which is converted into the sRGB value `n + 1`.
+#[rustfmt::skip]
+const SRGB_THRESHOLDS: [u16; 255] = [
+    2, 4, 7, 9, 12, 14, 17, 19, 22, 24, 27, 29,
+    32, 35, 38, 41, 45, 48, 52, 56, 60, 64, 68, 73,
+    78, 83, 88, 93, 98, 104, 110, 116, 122, 128, 135, 142,
+    149, 156, 163, 170, 178, 186, 194, 203, 211, 220, 229, 238,
+    247, 257, 267, 277, 287, 297, 308, 319, 330, 341, 353, 365,
+    377, 389, 401, 414, 427, 440, 454, 467, 481, 495, 509, 524,
+    539, 554, 569, 585, 600, 617, 633, 649, 666, 683, 700, 718,
+    736, 754, 772, 791, 809, 828, 848, 867, 887, 907, 928, 948,
+    969, 990, 1012, 1034, 1055, 1078, 1100, 1123, 1146, 1169, 1193, 1217,
+    1241, 1266, 1290, 1315, 1341, 1366, 1392, 1418, 1444, 1471, 1498, 1525,
+    1553, 1581, 1609, 1637, 1666, 1695, 1724, 1754, 1784, 1814, 1844, 1875,
+    1906, 1938, 1969, 2001, 2034, 2066, 2099, 2132, 2166, 2199, 2233, 2268,
+    2303, 2338, 2373, 2409, 2444, 2481, 2517, 2554, 2591, 2629, 2667, 2705,
+    2743, 2782, 2821, 2860, 2900, 2940, 2981, 3021, 3062, 3104, 3145, 3187,
+    3229, 3272, 3315, 3358, 3402, 3446, 3490, 3535, 3580, 3625, 3670, 3716,
+    3763, 3809, 3856, 3903, 3951, 3999, 4047, 4096, 4145, 4194, 4244, 4294,
+    4344, 4394, 4445, 4497, 4548, 4600, 4653, 4706, 4759, 4812, 4866, 4920,
+    4974, 5029, 5084, 5140, 5196, 5252, 5308, 5365, 5423, 5480, 5538, 5597,
+    5655, 5714, 5774, 5833, 5894, 5954, 6015, 6076, 6138, 6200, 6262, 6325,
+    6388, 6451, 6515, 6579, 6643, 6708, 6773, 6839, 6905, 6971, 7038, 7105,
+    7173, 7240, 7309, 7377, 7446, 7515, 7585, 7655, 7725, 7796, 7867, 7939,
+    8011, 8083, 8156,
+];
+
+/// Returns the s2.13 value of a channel.
+const fn channel(raw: u64, shift: u32) -> i16 {
+    (raw >> shift) as u16 as i16
+}
+
+/// Converts a linear color channel into an 8 bit sRGB value.
+///
+/// Values outside of the range `0.0..=1.0` are clamped.
+fn to_srgb(value: i16) -> u8 {
+    let value = value.clamp(0, ONE) as u16;
+
+    SRGB_THRESHOLDS.partition_point(|threshold| *threshold <= value) as u8
+}
+
+/// Converts an alpha channel into an 8 bit value.
+const fn to_alpha(value: i16) -> u8 {
+    let value = if value < 0 {
+        0
+    } else if value > ONE {
+        ONE
+    } else {
+        value
+    };
+
+    ((value as u32 * 255 + ONE as u32 / 2) / ONE as u32) as u8
+}
+
+/// Converts a raw 64 bpp pixel into an `ARGB8888` value.
+pub(crate) fn to_argb8888(raw: u64) -> u32 {
+    let b = to_srgb(channel(raw, 0));
+    let g = to_srgb(channel(raw, 16));
+    let r = to_srgb(channel(raw, 32));
+    let a = to_alpha(channel(raw, 48));
+
+    u32::from_be_bytes([a, r, g, b])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb() {
+        assert_eq!(to_srgb(0), 0);
+        assert_eq!(to_srgb(-ONE), 0);
+        assert_eq!(to_srgb(ONE), 255);
+        assert_eq!(to_srgb(2 * ONE), 255);
+        // 18% gray is close to the middle of the sRGB range.
+        assert_eq!(to_srgb(1475), 118);
+    }
+
+    #[test]
+    fn alpha() {
+        assert_eq!(to_alpha(0), 0);
+        assert_eq!(to_alpha(-1), 0);
+        assert_eq!(to_alpha(ONE / 2), 128);
+        assert_eq!(to_alpha(ONE), 255);
+        assert_eq!(to_alpha(i16::MAX), 255);
+    }
+
+    #[test]
+    fn argb8888() {
+        let raw = u64::from(ONE as u16) << 48 | u64::from(ONE as u16) << 32;
+        assert_eq!(to_argb8888(raw), 0xFFFF0000);
+    }
+}
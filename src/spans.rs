@@ -0,0 +1,213 @@
+//! Drawing of images with transparent pixels.
+
+use embedded_graphics::{
+    pixelcolor::raw::{RawU1, RawU16, RawU2, RawU24, RawU32, RawU4, RawU8},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{
+    raw_bmp::rle_area, scrgb, Bpp, CompressionMethod, Raw64Colors, RawBmp, RawColors, Rle4Colors,
+    Rle8Colors, RowOrder,
+};
+
+/// Maximum number of pixels which are buffered before a span is drawn.
+const SPAN_BUFFER_LEN: usize = 64;
+
+/// Draws a part of an image with transparent pixels.
+///
+/// `color` converts the raw value of each pixel inside `area` into a color and is called once per
+/// pixel, with the position of the pixel in the image. Pixels for which `color` returns `None` and
+/// undefined pixels in RLE compressed images aren't drawn.
+///
+/// The top left corner of `area` is drawn at the origin of `target`. `area` must be inside the
+/// image bounding box.
+pub(crate) fn fill_area<D, F>(
+    raw_bmp: &RawBmp<'_>,
+    target: &mut D,
+    area: &Rectangle,
+    color: F,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    F: FnMut(Point, u32) -> Option<D::Color>,
+{
+    let compression_method = raw_bmp.header().compression_method;
+
+    match raw_bmp.header().bpp {
+        Bpp::Bits1 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU1>::with_area(raw_bmp, area).map(|raw| u32::from(raw.into_inner())),
+            color,
+        ),
+        Bpp::Bits2 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU2>::with_area(raw_bmp, area).map(|raw| u32::from(raw.into_inner())),
+            color,
+        ),
+        Bpp::Bits4 if compression_method == CompressionMethod::Rle4 => fill_bottom_up(
+            target,
+            area,
+            rle_area::<Rle4Colors<'_>>(raw_bmp, area)
+                .map(|raw| raw.map(|raw| u32::from(raw.into_inner()))),
+            color,
+        ),
+        Bpp::Bits4 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU4>::with_area(raw_bmp, area).map(|raw| u32::from(raw.into_inner())),
+            color,
+        ),
+        Bpp::Bits8 if compression_method == CompressionMethod::Rle8 => fill_bottom_up(
+            target,
+            area,
+            rle_area::<Rle8Colors<'_>>(raw_bmp, area)
+                .map(|raw| raw.map(|raw| u32::from(raw.into_inner()))),
+            color,
+        ),
+        Bpp::Bits8 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU8>::with_area(raw_bmp, area).map(|raw| u32::from(raw.into_inner())),
+            color,
+        ),
+        Bpp::Bits16 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU16>::with_area(raw_bmp, area).map(|raw| u32::from(raw.into_inner())),
+            color,
+        ),
+        Bpp::Bits24 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU24>::with_area(raw_bmp, area).map(RawU24::into_inner),
+            color,
+        ),
+        Bpp::Bits32 => fill_top_down(
+            target,
+            area,
+            RawColors::<RawU32>::with_area(raw_bmp, area).map(RawU32::into_inner),
+            color,
+        ),
+        Bpp::Bits64 => fill_top_down(
+            target,
+            area,
+            Raw64Colors::with_area(raw_bmp, area).map(scrgb::to_argb8888),
+            color,
+        ),
+    }
+}
+
+/// Draws raw pixel values, which are stored in top-down order.
+fn fill_top_down<D, I, F>(
+    target: &mut D,
+    area: &Rectangle,
+    raw: I,
+    mut color: F,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    I: Iterator<Item = u32>,
+    F: FnMut(Point, u32) -> Option<D::Color>,
+{
+    let colors = area.points().zip(raw).map(|(p, raw)| color(p, raw));
+
+    fill_opaque_spans(target, area.size, RowOrder::TopDown, colors)
+}
+
+/// Draws raw pixel values of an RLE compressed image, which are stored in bottom-up order.
+///
+/// Undefined pixels are represented by `None`.
+fn fill_bottom_up<D, I, F>(
+    target: &mut D,
+    area: &Rectangle,
+    raw: I,
+    mut color: F,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    I: Iterator<Item = Option<u32>>,
+    F: FnMut(Point, u32) -> Option<D::Color>,
+{
+    let Rectangle { top_left, size } = *area;
+    let points = (0..size.height)
+        .rev()
+        .flat_map(move |y| (0..size.width).map(move |x| top_left + Point::new(x as i32, y as i32)));
+    let colors = points
+        .zip(raw)
+        .map(|(p, raw)| raw.and_then(|raw| color(p, raw)));
+
+    fill_opaque_spans(target, size, RowOrder::BottomUp, colors)
+}
+
+/// Draws an image with transparent pixels.
+///
+/// Transparent pixels are represented by `None` and are skipped. Consecutive opaque pixels in a
+/// row are buffered and drawn with a single call to [`DrawTarget::fill_contiguous`], which avoids
+/// falling back to per-pixel drawing for the entire image. Spans which are longer than the buffer
+/// are split into multiple calls.
+///
+/// `pixels` must return the pixels in the given row order. Each pixel is only read once.
+pub(crate) fn fill_opaque_spans<D, I>(
+    target: &mut D,
+    size: Size,
+    row_order: RowOrder,
+    pixels: I,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+    I: IntoIterator<Item = Option<D::Color>>,
+{
+    let mut pixels = pixels.into_iter();
+    let mut span = [None; SPAN_BUFFER_LEN];
+
+    for row in 0..size.height {
+        let y = match row_order {
+            RowOrder::TopDown => row,
+            RowOrder::BottomUp => size.height - 1 - row,
+        };
+
+        let mut start = 0;
+        let mut len = 0;
+        for x in 0..size.width {
+            let Some(pixel) = pixels.next() else {
+                // The pixel data ended before the end of the image.
+                return fill_span(target, Point::new(start as i32, y as i32), &span[..len]);
+            };
+
+            if let Some(color) = pixel {
+                if len == 0 {
+                    start = x;
+                }
+                span[len] = Some(color);
+                len += 1;
+
+                if len < SPAN_BUFFER_LEN {
+                    continue;
+                }
+            }
+
+            fill_span(target, Point::new(start as i32, y as i32), &span[..len])?;
+            len = 0;
+        }
+
+        fill_span(target, Point::new(start as i32, y as i32), &span[..len])?;
+    }
+
+    Ok(())
+}
+
+/// Draws a span of buffered opaque pixels, which starts at `position`.
+fn fill_span<D>(target: &mut D, position: Point, span: &[Option<D::Color>]) -> Result<(), D::Error>
+where
+    D: DrawTarget,
+{
+    if span.is_empty() {
+        return Ok(());
+    }
+
+    let area = Rectangle::new(position, Size::new(span.len() as u32, 1));
+    target.fill_contiguous(&area, span.iter().flatten().copied())
+}
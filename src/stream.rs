@@ -0,0 +1,449 @@
+use core::{convert::Infallible, marker::PhantomData};
+
+use embedded_graphics::{
+    pixelcolor::{raw::RawData, Rgb555, Rgb565, Rgb888},
+    prelude::*,
+};
+use embedded_io::{Read, ReadExactError, Seek, SeekFrom};
+
+use crate::{
+    lookup_table::LookupTable, raw_bmp::ColorType, raw_iter::RleRows, spans::fill_opaque_spans,
+    Bmp, ColorTable, CompressionMethod, Header, ParseError, RawBmp, Rle4Colors, Rle8Colors,
+    RowOrder,
+};
+
+/// Maximum length of the headers and color table.
+///
+/// The buffer is large enough for a file header, a `BITMAPV5HEADER`, separately stored channel
+/// masks and a color table with 256 entries.
+const HEADER_BUFFER_LEN: usize = 14 + 124 + 16 + MAX_COLOR_TABLE_LEN;
+
+/// Maximum length of the color table in bytes.
+const MAX_COLOR_TABLE_LEN: usize = 256 * 4;
+
+/// BMP image which is streamed from a reader.
+///
+/// [`Bmp`] requires the complete file to be stored in memory, which isn't possible for large
+/// images on devices with a small amount of RAM. `StreamingBmp` only stores the header and the
+/// color table and reads the image data from an [`embedded_io::Read`] source while it is drawn.
+///
+/// The image data is read into a row buffer, which is provided by the caller. For uncompressed
+/// images the buffer must be large enough to store at least one row, larger buffers are used to
+/// read and draw multiple rows at once. RLE compressed images are decoded in chunks of complete
+/// rows, which requires the buffer to be large enough to store the compressed data of at least one
+/// row. [`row_buffer_len`](Self::row_buffer_len) returns the recommended buffer length.
+///
+/// Color tables with more than 256 entries aren't supported.
+///
+/// This struct is only available if the `embedded-io` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use tinybmp::StreamingBmp;
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb565> = MockDisplay::default();
+/// # display.set_allow_overdraw(true);
+///
+/// // Any `embedded_io::Read` implementation can be used, for example a file on an SD card.
+/// # let mut reader = include_bytes!("../tests/chessboard-8px-color-16bit.bmp").as_slice();
+/// let bmp = StreamingBmp::<Rgb565>::from_reader(&mut reader).unwrap();
+///
+/// let mut row_buffer = [0; 64];
+/// bmp.draw(&mut reader, &mut display.translated(Point::new(10, 20)), &mut row_buffer)
+///     .unwrap();
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct StreamingBmp<C> {
+    header: Header,
+    color_type: ColorType,
+    color_table: [u8; MAX_COLOR_TABLE_LEN],
+    color_table_len: usize,
+    color_table_entry_len: usize,
+    color: PhantomData<C>,
+}
+
+impl<C> StreamingBmp<C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    /// Reads the header and the color table of a BMP file.
+    ///
+    /// The reader must be positioned at the start of the file. After the header was parsed the
+    /// reader is positioned at the start of the image data, which makes it possible to call
+    /// [`draw`](Self::draw) without seeking.
+    pub fn from_reader<R>(reader: &mut R) -> Result<Self, StreamError<R::Error>>
+    where
+        R: Read,
+    {
+        let mut buffer = [0; HEADER_BUFFER_LEN];
+
+        // The image data offset in the file header determines how much data needs to be read.
+        read_exact(reader, &mut buffer[0..14])?;
+        let image_data_start =
+            u32::from_le_bytes([buffer[10], buffer[11], buffer[12], buffer[13]]) as usize;
+
+        let header_len = image_data_start.clamp(14, HEADER_BUFFER_LEN);
+        read_exact(reader, &mut buffer[14..header_len])?;
+
+        let (_, (header, color_table)) =
+            Header::parse(&buffer[..header_len]).map_err(StreamError::Parse)?;
+        let color_type = ColorType::from_header(&header).map_err(StreamError::Parse)?;
+
+        let mut bmp = Self {
+            header,
+            color_type,
+            color_table: [0; MAX_COLOR_TABLE_LEN],
+            color_table_len: 0,
+            color_table_entry_len: 4,
+            color: PhantomData,
+        };
+
+        if let Some(color_table) = color_table {
+            // Color tables can contain more entries than are addressable by the pixel data. Extra
+            // entries are ignored.
+            let entry_len = color_table.entry_len();
+            let data = color_table.as_bytes();
+            let len = data.len().min(MAX_COLOR_TABLE_LEN / entry_len * entry_len);
+            bmp.color_table[..len].copy_from_slice(&data[..len]);
+            bmp.color_table_len = len;
+            bmp.color_table_entry_len = entry_len;
+        }
+
+        // Skip the gap between the headers and the image data.
+        let mut gap = image_data_start.saturating_sub(header_len);
+        while gap > 0 {
+            let len = gap.min(buffer.len());
+            read_exact(reader, &mut buffer[..len])?;
+            gap -= len;
+        }
+
+        Ok(bmp)
+    }
+
+    /// Returns the BMP header.
+    pub const fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the color table associated with the image.
+    pub fn color_table(&self) -> Option<ColorTable<'_>> {
+        (self.color_table_len > 0).then(|| {
+            ColorTable::with_entry_len(
+                &self.color_table[..self.color_table_len],
+                self.color_table_entry_len,
+            )
+        })
+    }
+
+    /// Returns the recommended length of the row buffer.
+    ///
+    /// For uncompressed images this is the length of a single row. For RLE compressed images the
+    /// length is based on the worst case length of a compressed row without delta escapes.
+    pub const fn row_buffer_len(&self) -> usize {
+        match self.header.compression_method {
+            CompressionMethod::Rle8 | CompressionMethod::Rle4 => {
+                // Each pixel requires at most two bytes, plus the end of line and end of bitmap
+                // markers.
+                self.header.image_size.width as usize * 2 + 4
+            }
+            _ => self.header.bytes_per_row(),
+        }
+    }
+
+    /// Seeks to the start of the image data.
+    ///
+    /// This makes it possible to draw the image again after it was drawn. The image data offset
+    /// is relative to the start of the BMP file, which needs to be at position `0` of the reader.
+    pub fn seek_to_image_data<S>(&self, reader: &mut S) -> Result<(), S::Error>
+    where
+        S: Seek,
+    {
+        reader
+            .seek(SeekFrom::Start(self.header.image_data_start as u64))
+            .map(|_| ())
+    }
+
+    /// Reads the image data from `reader` and draws the image to `target`.
+    ///
+    /// The reader must be positioned at the start of the image data. The top left corner of the
+    /// image is drawn at the origin of `target`. `row_buffer` is used to store the image data
+    /// while it is drawn, see [`row_buffer_len`](Self::row_buffer_len) for the required length.
+    ///
+    /// Rows are drawn in the order they are stored in the file, which means that bottom-up images
+    /// are drawn starting at the bottom row.
+    pub fn draw<R, D>(
+        &self,
+        reader: &mut R,
+        target: &mut D,
+        row_buffer: &mut [u8],
+    ) -> Result<(), StreamError<R::Error, D::Error>>
+    where
+        R: Read,
+        D: DrawTarget<Color = C>,
+    {
+        // Embedded JPEG and PNG images aren't decoded.
+        if self.header.compression_method.is_embedded_image() {
+            return Ok(());
+        }
+
+        // Indexed images without a color table aren't drawn, like in `Bmp`.
+        let bmp = self.bmp(self.header, &[]);
+        if bmp.is_indexed() && self.color_table().is_none() {
+            return Ok(());
+        }
+
+        match self.header.compression_method {
+            CompressionMethod::Rle8 | CompressionMethod::Rle4 => {
+                self.draw_rle(reader, target, row_buffer)
+            }
+            _ => self.draw_rows(reader, target, row_buffer),
+        }
+    }
+
+    /// Creates a `Bmp` for a part of the image data.
+    fn bmp<'a>(&'a self, header: Header, image_data: &'a [u8]) -> Bmp<'a, C> {
+        Bmp {
+            raw_bmp: RawBmp::from_parts(
+                header,
+                self.color_type,
+                self.color_table(),
+                image_data,
+                None,
+            ),
+            color_type: PhantomData,
+        }
+    }
+
+    /// Returns a lookup table for the colors of indexed images.
+    fn lookup_table(&self) -> LookupTable<C, impl Fn(u8) -> C + '_> {
+        let color_table = self.color_table();
+        let fallback_color = C::from(Rgb888::BLACK);
+
+        LookupTable::new(self.color_type, move |index| {
+            color_table
+                .and_then(|color_table| color_table.get(u32::from(index)))
+                .map_or(fallback_color, Into::into)
+        })
+    }
+
+    /// Draws an uncompressed image.
+    ///
+    /// As many complete rows as fit into the row buffer are read and drawn at once.
+    fn draw_rows<R, D>(
+        &self,
+        reader: &mut R,
+        target: &mut D,
+        row_buffer: &mut [u8],
+    ) -> Result<(), StreamError<R::Error, D::Error>>
+    where
+        R: Read,
+        D: DrawTarget<Color = C>,
+    {
+        let size = self.header.image_size;
+        let height = size.height as usize;
+        let bytes_per_row = self.header.bytes_per_row();
+
+        let chunk_rows = row_buffer.len() / bytes_per_row;
+        if chunk_rows == 0 {
+            return Err(StreamError::BufferTooSmall);
+        }
+
+        let lookup_table = self.lookup_table();
+
+        let mut row = 0;
+        while row < height {
+            let rows = chunk_rows.min(height - row);
+            let image_data = &mut row_buffer[..rows * bytes_per_row];
+            read_exact(reader, image_data)?;
+
+            let header = Header {
+                image_size: Size::new(size.width, rows as u32),
+                ..self.header
+            };
+            let bmp = self.bmp(header, image_data);
+
+            let y = match self.header.row_order {
+                RowOrder::TopDown => row,
+                RowOrder::BottomUp => height - row - rows,
+            };
+            let target = &mut target.translated(Point::new(0, y as i32));
+
+            if bmp.is_indexed() {
+                bmp.draw_indexed_area(target, &bmp.bounding_box(), |index| lookup_table.get(index))
+            } else {
+                bmp.draw(target)
+            }
+            .map_err(StreamError::Draw)?;
+
+            row += rows;
+        }
+
+        Ok(())
+    }
+
+    /// Draws an RLE compressed image.
+    ///
+    /// The row buffer is used as a sliding window over the compressed image data. All complete
+    /// rows in the window are drawn and the data of the remaining incomplete row is moved to the
+    /// start of the buffer before it is refilled.
+    fn draw_rle<R, D>(
+        &self,
+        reader: &mut R,
+        target: &mut D,
+        row_buffer: &mut [u8],
+    ) -> Result<(), StreamError<R::Error, D::Error>>
+    where
+        R: Read,
+        D: DrawTarget<Color = C>,
+    {
+        let lookup_table = self.lookup_table();
+
+        let mut remaining = self.header.image_data_len as usize;
+        let mut len = 0;
+        let mut start_row = 0;
+
+        loop {
+            let read_len = remaining.min(row_buffer.len() - len);
+            read_exact(reader, &mut row_buffer[len..len + read_len])?;
+            len += read_len;
+            remaining -= read_len;
+
+            let is_last = remaining == 0;
+            let window =
+                RawBmp::from_parts(self.header, self.color_type, None, &row_buffer[..len], None);
+
+            let (end_row, offset) = match self.header.compression_method {
+                CompressionMethod::Rle8 => draw_rle_window::<Rle8Colors<'_>, _, _, _>(
+                    &window,
+                    target,
+                    start_row,
+                    is_last,
+                    &lookup_table,
+                ),
+                _ => draw_rle_window::<Rle4Colors<'_>, _, _, _>(
+                    &window,
+                    target,
+                    start_row,
+                    is_last,
+                    &lookup_table,
+                ),
+            }
+            .map_err(StreamError::Draw)?;
+
+            if is_last {
+                return Ok(());
+            }
+
+            if end_row == start_row {
+                return Err(StreamError::BufferTooSmall);
+            }
+
+            row_buffer.copy_within(offset..len, 0);
+            len -= offset;
+            start_row = end_row;
+        }
+    }
+}
+
+impl<C> OriginDimensions for StreamingBmp<C>
+where
+    C: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.header.image_size
+    }
+}
+
+/// Draws the complete rows in a window of RLE compressed image data.
+///
+/// The window must start at the beginning of `start_row`. If `is_last` is `true` the window
+/// contains the remaining image data and all rows are drawn. Returns the first row which wasn't
+/// drawn and its offset in the window.
+fn draw_rle_window<'a, R, P, D, F>(
+    window: &RawBmp<'a>,
+    target: &mut D,
+    start_row: u32,
+    is_last: bool,
+    lookup_table: &LookupTable<D::Color, F>,
+) -> Result<(u32, usize), D::Error>
+where
+    R: RleRows<'a> + Iterator<Item = Option<P>>,
+    P: RawData<Storage = u8>,
+    D: DrawTarget,
+    F: Fn(u8) -> D::Color,
+{
+    let size = window.header().image_size;
+
+    let (end_row, offset) = if is_last {
+        (size.height, window.image_data().len())
+    } else {
+        // Find the start of the last row, which starts in the window.
+        let mut end = (start_row, 0);
+        let mut decoder = R::at_row_start(window, start_row, 0);
+        loop {
+            if let Some(row_start) = decoder.row_start() {
+                end = row_start;
+            }
+
+            if decoder.next().is_none() {
+                break;
+            }
+        }
+        end
+    };
+
+    let end_row = end_row.min(size.height);
+    if end_row > start_row {
+        let rows = end_row - start_row;
+        let len = size.width as usize * rows as usize;
+
+        let colors = R::at_row_start(window, start_row, 0)
+            .take(len)
+            .map(|index| index.map(|index| lookup_table.get(index.into_inner())));
+
+        // RLE compressed images are always stored bottom-up.
+        let y = size.height - end_row;
+        fill_opaque_spans(
+            &mut target.translated(Point::new(0, y as i32)),
+            Size::new(size.width, rows),
+            RowOrder::BottomUp,
+            colors,
+        )?;
+    }
+
+    Ok((end_row, offset))
+}
+
+/// Reads the exact number of bytes required to fill `buffer`.
+fn read_exact<R, D>(reader: &mut R, buffer: &mut [u8]) -> Result<(), StreamError<R::Error, D>>
+where
+    R: Read,
+{
+    reader.read_exact(buffer).map_err(|e| match e {
+        ReadExactError::UnexpectedEof => StreamError::Parse(ParseError::UnexpectedEndOfFile),
+        ReadExactError::Other(e) => StreamError::Read(e),
+    })
+}
+
+/// Streaming error.
+///
+/// `E` is the error type of the reader and `D` is the error type of the draw target.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum StreamError<E, D = Infallible> {
+    /// Reading from the reader failed.
+    Read(E),
+
+    /// The image couldn't be parsed.
+    ///
+    /// [`ParseError::UnexpectedEndOfFile`] is returned if the reader reaches the end of the data
+    /// before the complete image was read.
+    Parse(ParseError),
+
+    /// The row buffer is too small to store a single row.
+    BufferTooSmall,
+
+    /// Drawing to the draw target failed.
+    Draw(D),
+}
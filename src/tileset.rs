@@ -0,0 +1,183 @@
+use embedded_graphics::{
+    image::{ImageDrawableExt, SubImage},
+    pixelcolor::{Rgb555, Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::Bmp;
+
+/// Tileset or sprite atlas stored in a single BMP image.
+///
+/// The image is divided into a grid of equally sized tiles, which are numbered row by row,
+/// starting at the top left corner. Tiles can optionally be separated by `spacing` pixels and the
+/// first tile can be offset from the top left corner of the image by `margin` pixels. Incomplete
+/// tiles at the right and bottom edges of the image are ignored.
+///
+/// In addition to the tile grid a tileset can contain named regions, which can be used to access
+/// sprites of different sizes in the same image.
+///
+/// Tiles and regions are returned as [`SubImage`]s, which can be drawn by wrapping them in an
+/// [`Image`](embedded_graphics::image::Image). Drawing a tile only decodes the pixels inside the
+/// tile, see [`Bmp`] for more information.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::{image::Image, pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+/// use tinybmp::{Bmp, Tileset};
+/// # use embedded_graphics::mock_display::MockDisplay;
+/// # let mut display: MockDisplay<Rgb888> = MockDisplay::default();
+///
+/// let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("../tests/chessboard-8px-24bit.bmp")).unwrap();
+///
+/// const REGIONS: &[(&str, Rectangle)] = &[(
+///     "border",
+///     Rectangle::new(Point::new(0, 0), Size::new(8, 1)),
+/// )];
+///
+/// let tileset = Tileset::new(bmp, Size::new(2, 2)).with_regions(REGIONS);
+/// assert_eq!(tileset.len(), 16);
+///
+/// // Draw the fifth tile, which is the first tile in the second row.
+/// let tile = tileset.tile(4).unwrap();
+/// Image::new(&tile, Point::new(10, 20)).draw(&mut display)?;
+///
+/// // Draw a named region.
+/// let border = tileset.region("border").unwrap();
+/// Image::new(&border, Point::new(10, 30)).draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Tileset<'a, C> {
+    bmp: Bmp<'a, C>,
+    tile_size: Size,
+    spacing: u32,
+    margin: u32,
+    regions: &'a [(&'a str, Rectangle)],
+}
+
+impl<'a, C> Tileset<'a, C>
+where
+    C: PixelColor + From<Rgb555> + From<Rgb565> + From<Rgb888>,
+{
+    /// Creates a tileset with the given tile size.
+    ///
+    /// The tiles aren't separated by spacing and the first tile starts at the top left corner of
+    /// the image.
+    pub const fn new(bmp: Bmp<'a, C>, tile_size: Size) -> Self {
+        Self {
+            bmp,
+            tile_size,
+            spacing: 0,
+            margin: 0,
+            regions: &[],
+        }
+    }
+
+    /// Sets the spacing between adjacent tiles in pixels.
+    pub const fn with_spacing(self, spacing: u32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Sets the offset of the first tile from the top left corner of the image in pixels.
+    pub const fn with_margin(self, margin: u32) -> Self {
+        Self { margin, ..self }
+    }
+
+    /// Sets the named regions.
+    ///
+    /// Each region is defined by a name and its area in the image. See [`region`](Self::region)
+    /// for more information.
+    pub const fn with_regions(self, regions: &'a [(&'a str, Rectangle)]) -> Self {
+        Self { regions, ..self }
+    }
+
+    /// Returns a reference to the wrapped BMP image.
+    pub const fn as_bmp(&self) -> &Bmp<'a, C> {
+        &self.bmp
+    }
+
+    /// Returns the tile size.
+    pub const fn tile_size(&self) -> Size {
+        self.tile_size
+    }
+
+    /// Returns the number of tiles in each row.
+    pub fn columns(&self) -> u32 {
+        grid_len(
+            self.bmp.size().width,
+            self.tile_size.width,
+            self.spacing,
+            self.margin,
+        )
+    }
+
+    /// Returns the number of rows of tiles.
+    pub fn rows(&self) -> u32 {
+        grid_len(
+            self.bmp.size().height,
+            self.tile_size.height,
+            self.spacing,
+            self.margin,
+        )
+    }
+
+    /// Returns the number of tiles.
+    pub fn len(&self) -> usize {
+        self.columns() as usize * self.rows() as usize
+    }
+
+    /// Returns `true` if the tileset doesn't contain any tiles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the area of a tile in the image.
+    ///
+    /// `None` is returned if `index` is out of bounds.
+    pub fn tile_area(&self, index: usize) -> Option<Rectangle> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let columns = self.columns() as usize;
+        let column = (index % columns) as u32;
+        let row = (index / columns) as u32;
+
+        let top_left = Point::new(
+            (self.margin + column * (self.tile_size.width + self.spacing)) as i32,
+            (self.margin + row * (self.tile_size.height + self.spacing)) as i32,
+        );
+
+        Some(Rectangle::new(top_left, self.tile_size))
+    }
+
+    /// Returns a tile.
+    ///
+    /// `None` is returned if `index` is out of bounds.
+    pub fn tile(&self, index: usize) -> Option<SubImage<'_, Bmp<'a, C>>> {
+        self.tile_area(index).map(|area| self.bmp.sub_image(&area))
+    }
+
+    /// Returns a named region.
+    ///
+    /// `None` is returned if the tileset doesn't contain a region with the given name. Parts of
+    /// the region which are outside of the image aren't drawn.
+    pub fn region(&self, name: &str) -> Option<SubImage<'_, Bmp<'a, C>>> {
+        self.regions
+            .iter()
+            .find(|(region_name, _)| *region_name == name)
+            .map(|(_, area)| self.bmp.sub_image(area))
+    }
+}
+
+/// Returns the number of complete tiles along one axis of the image.
+fn grid_len(image_len: u32, tile_len: u32, spacing: u32, margin: u32) -> u32 {
+    if tile_len == 0 {
+        return 0;
+    }
+
+    // The last tile isn't followed by spacing.
+    (image_len.saturating_sub(margin) + spacing) / (tile_len + spacing)
+}
@@ -0,0 +1,172 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{AlphaMode, Bmp, ChannelMasks, CompressionMethod};
+
+const ARGB8888: &[u8] = include_bytes!("./colors_argb8888.bmp");
+const ARGB1555: &[u8] = include_bytes!("./colors_argb1555.bmp");
+
+fn draw<I: ImageDrawable>(image_drawable: &I) -> MockDisplay<I::Color> {
+    let mut display = MockDisplay::new();
+    Image::new(image_drawable, Point::zero())
+        .draw(&mut display)
+        .unwrap();
+
+    display
+}
+
+#[test]
+fn alpha_values() {
+    let bmp = Bmp::<Rgb888>::from_slice(ARGB8888).unwrap();
+
+    assert!(bmp.as_raw().has_alpha());
+    assert_eq!(bmp.alpha(Point::new(0, 0)), Some(0));
+    assert_eq!(bmp.alpha(Point::new(1, 0)), Some(255));
+    assert_eq!(bmp.alpha(Point::new(2, 0)), Some(128));
+    assert_eq!(bmp.alpha(Point::new(1, 1)), Some(64));
+    assert_eq!(bmp.alpha(Point::new(4, 0)), None);
+}
+
+#[test]
+fn alpha_bitfields() {
+    let data = include_bytes!("./colors_argb8888_alphabitfields.bmp");
+
+    // The image size field can be set to zero for uncompressed images.
+    let mut zero_image_size = data.to_vec();
+    zero_image_size[34..38].copy_from_slice(&0u32.to_le_bytes());
+
+    for data in [data.as_slice(), &zero_image_size] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        let expected = Bmp::<Rgb888>::from_slice(ARGB8888).unwrap();
+
+        assert_eq!(
+            bmp.as_raw().header().compression_method,
+            CompressionMethod::AlphaBitfields
+        );
+        assert_eq!(
+            bmp.as_raw().header().channel_masks,
+            Some(ChannelMasks::ARGB8888)
+        );
+        assert_eq!(bmp.as_raw().image_data().len(), 32);
+
+        for p in expected.bounding_box().points() {
+            assert_eq!(bmp.pixel(p), expected.pixel(p));
+            assert_eq!(bmp.alpha(p), expected.alpha(p));
+        }
+    }
+}
+
+#[test]
+fn alpha_rgb64() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb64.bmp")).unwrap();
+
+    // The bottom row contains a half transparent cyan and a fully transparent white pixel.
+    draw(&bmp.with_alpha(AlphaMode::Threshold(129))).assert_pattern(&[
+        "KRGY", //
+        "BM  ", //
+    ]);
+    draw(&bmp.with_alpha(AlphaMode::Threshold(128))).assert_pattern(&[
+        "KRGY", //
+        "BMC ", //
+    ]);
+}
+
+#[test]
+fn alpha_values_without_alpha_channel() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_32bit.bmp")).unwrap();
+
+    assert!(!bmp.as_raw().has_alpha());
+    assert_eq!(bmp.alpha(Point::new(0, 0)), Some(255));
+}
+
+#[test]
+fn ignore_alpha() {
+    let bmp = Bmp::<Rgb888>::from_slice(ARGB8888).unwrap();
+
+    draw(&bmp).assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn skip_transparent() {
+    let bmp = Bmp::<Rgb888>::from_slice(ARGB8888).unwrap();
+    let image = bmp.with_alpha(AlphaMode::Threshold(1));
+
+    draw(&image).assert_pattern(&[
+        " RG ", //
+        "BM W", //
+    ]);
+
+    assert_eq!(image.pixel(Point::new(0, 0)), None);
+    assert_eq!(image.pixel(Point::new(1, 0)), Some(Rgb888::RED));
+}
+
+#[test]
+fn sub_image() {
+    let bmp = Bmp::<Rgb888>::from_slice(ARGB8888).unwrap();
+    let image = bmp.with_alpha(AlphaMode::Threshold(1));
+
+    draw(&image.sub_image(&Rectangle::new(Point::new(1, 0), Size::new(3, 2)))).assert_pattern(&[
+        "RG ", //
+        "M W", //
+    ]);
+}
+
+#[test]
+fn threshold() {
+    let bmp = Bmp::<Rgb565>::from_slice(ARGB8888).unwrap();
+
+    draw(&bmp.with_alpha(AlphaMode::Threshold(128))).assert_pattern(&[
+        " RG ", //
+        "B  W", //
+    ]);
+}
+
+#[test]
+fn blend_with_background() {
+    let bmp = Bmp::<Rgb888>::from_slice(ARGB8888).unwrap();
+    let image = bmp.with_alpha(AlphaMode::Background(Rgb888::WHITE));
+
+    assert_eq!(image.pixel(Point::new(0, 0)), Some(Rgb888::WHITE));
+    assert_eq!(image.pixel(Point::new(1, 0)), Some(Rgb888::RED));
+    assert_eq!(
+        image.pixel(Point::new(2, 0)),
+        Some(Rgb888::new(127, 255, 127))
+    );
+    assert_eq!(
+        image.pixel(Point::new(1, 1)),
+        Some(Rgb888::new(255, 191, 255))
+    );
+
+    let display = draw(&image);
+    for p in image.bounding_box().points() {
+        assert_eq!(display.get_pixel(p), image.pixel(p), "pixel at {p}");
+    }
+}
+
+#[test]
+fn argb1555() {
+    let bmp = Bmp::<Rgb888>::from_slice(ARGB1555).unwrap();
+    assert!(bmp.as_raw().has_alpha());
+
+    draw(&bmp.with_alpha(AlphaMode::Threshold(1))).assert_pattern(&[
+        " RG ", //
+        "B  W", //
+    ]);
+}
+
+#[test]
+fn image_without_alpha_channel() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_32bit.bmp")).unwrap();
+
+    draw(&bmp.with_alpha(AlphaMode::Threshold(255))).assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
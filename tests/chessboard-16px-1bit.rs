@@ -1,7 +1,4 @@
-use embedded_graphics::{
-    pixelcolor::{BinaryColor, Rgb888},
-    prelude::*,
-};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
 use tinybmp::{Bmp, Bpp, CompressionMethod, Header, RawBmp, RowOrder};
 
 #[test]
@@ -20,6 +17,10 @@ fn chessboard_16px_1bit() {
             channel_masks: None,
             row_order: RowOrder::BottomUp,
             compression_method: CompressionMethod::Rgb,
+            pixels_per_meter: Size::new(2834, 2834),
+            color_planes: 1,
+            colors_important: 2,
+            color_space: None,
         }
     );
 
@@ -74,7 +75,7 @@ fn chessboard_16px_1bit_iter() {
 
     let pixels: Vec<u32> = bmp
         .pixels()
-        .map(|Pixel(_pos, color)| color.into_storage().into())
+        .map(|Pixel(_pos, color)| color.into_storage())
         .collect();
 
     // 16px x 16px image. Check that iterator returns all pixels in it
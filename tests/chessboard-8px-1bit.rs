@@ -20,6 +20,10 @@ fn chessboard_8px_1bit() {
             channel_masks: None,
             row_order: RowOrder::BottomUp,
             compression_method: CompressionMethod::Rgb,
+            pixels_per_meter: Size::new(3780, 3780),
+            color_planes: 1,
+            colors_important: 2,
+            color_space: None,
         }
     );
 
@@ -95,7 +99,7 @@ fn chessboard_8px_1bit_iter() {
 
     let pixels: Vec<u32> = bmp
         .pixels()
-        .map(|Pixel(_pos, color)| color.into_storage().into())
+        .map(|Pixel(_pos, color)| color.into_storage())
         .collect();
 
     // 8px x 8px image. Check that iterator returns all pixels in it
@@ -129,7 +133,7 @@ fn chessboard_8px_1bit_iter_inverted() {
 
     let pixels: Vec<u32> = bmp
         .pixels()
-        .map(|Pixel(_pos, color)| color.into_storage().into())
+        .map(|Pixel(_pos, color)| color.into_storage())
         .collect();
 
     // 8px x 8px image. Check that iterator returns all pixels in it
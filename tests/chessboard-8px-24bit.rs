@@ -18,6 +18,12 @@ fn chessboard_8px_24bit() {
             channel_masks: None,
             row_order: RowOrder::BottomUp,
             compression_method: CompressionMethod::Rgb,
+            pixels_per_meter: Size::new(2835, 2835),
+            color_planes: 1,
+            colors_important: 0,
+            // The V4 header of this file doesn't contain valid color space information, because
+            // the channel masks are missing and the following fields are shifted.
+            color_space: bmp.header().color_space,
         }
     );
 
@@ -1,5 +1,8 @@
 use embedded_graphics::prelude::*;
-use tinybmp::{Bpp, ChannelMasks, CompressionMethod, Header, RawBmp, RowOrder};
+use tinybmp::{
+    Bpp, ChannelMasks, CieXyz, ColorSpace, ColorSpaceInfo, CompressionMethod, Header, RawBmp,
+    RenderingIntent, RowOrder,
+};
 
 #[test]
 fn chessboard_8px_color_16bit() {
@@ -17,6 +20,17 @@ fn chessboard_8px_color_16bit() {
             channel_masks: Some(ChannelMasks::RGB565),
             row_order: RowOrder::BottomUp,
             compression_method: CompressionMethod::Bitfields,
+            pixels_per_meter: Size::new(2835, 2835),
+            color_planes: 1,
+            colors_important: 0,
+            color_space: Some(ColorSpaceInfo {
+                color_space: ColorSpace::Srgb,
+                endpoints: [CieXyz::default(); 3],
+                gamma: [0; 3],
+                intent: Some(RenderingIntent::RelativeColorimetric),
+                profile_offset: 0,
+                profile_size: 0,
+            }),
         }
     );
 
@@ -0,0 +1,121 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, ColorKey};
+
+const RGB888: &[u8] = include_bytes!("./colors_rgb888_24bit.bmp");
+const RLE8_DELTA: &[u8] = include_bytes!("./rle8_delta.bmp");
+const RLE4_DELTA: &[u8] = include_bytes!("./rle4_delta.bmp");
+
+#[test]
+fn transparent_color() {
+    let bmp = Bmp::<Rgb888>::from_slice(RGB888)
+        .unwrap()
+        .with_transparent_color(Rgb888::RED);
+    assert_eq!(bmp.key(), ColorKey::Color(Rgb888::RED));
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+    display.assert_pattern(&[
+        "K GY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn transparent_color_is_compared_after_conversion() {
+    let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("./chessboard-8px-24bit.bmp"))
+        .unwrap()
+        .with_transparent_color(Rgb565::BLACK);
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+    display.assert_pattern(&[
+        "WW  WW  ", //
+        "WW  WW  ", //
+        "  WW  WW", //
+        "  WW  WW", //
+        "WW  WW  ", //
+        "WW  WW  ", //
+        "  WW  WW", //
+        "  WW  WW", //
+    ]);
+}
+
+#[test]
+fn transparent_index() {
+    for data in [
+        RLE8_DELTA,
+        RLE4_DELTA,
+        include_bytes!("./colors_gray2_2bpp.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        let keyed = bmp.with_transparent_index(2);
+
+        let mut display = MockDisplay::new();
+        Image::new(&keyed, Point::zero())
+            .draw(&mut display)
+            .unwrap();
+
+        for p in bmp.bounding_box().points() {
+            let index = bmp.as_raw().pixel(p);
+            let expected = if index == Some(2) { None } else { bmp.pixel(p) };
+
+            assert_eq!(display.get_pixel(p), expected, "pixel at {p}");
+            assert_eq!(keyed.pixel(p), expected, "pixel at {p}");
+        }
+    }
+}
+
+#[test]
+fn transparent_index_without_color_table() {
+    let bmp = Bmp::<Rgb888>::from_slice(RGB888).unwrap();
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp.with_transparent_index(0), Point::zero())
+        .draw(&mut display)
+        .unwrap();
+
+    display.assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn get_pixel() {
+    let bmp = Bmp::<Rgb888>::from_slice(RGB888)
+        .unwrap()
+        .with_transparent_color(Rgb888::WHITE);
+
+    assert_eq!(bmp.pixel(Point::new(1, 0)), Some(Rgb888::RED));
+    assert_eq!(bmp.pixel(Point::new(3, 1)), None);
+    assert_eq!(bmp.pixel(Point::new(4, 1)), None);
+}
+
+#[test]
+fn sub_image() {
+    let bmp = Bmp::<Rgb888>::from_slice(RLE8_DELTA)
+        .unwrap()
+        .with_transparent_color(Rgb888::GREEN);
+
+    let mut display = MockDisplay::new();
+    Image::new(
+        &bmp.sub_image(&Rectangle::new(Point::new(1, 1), Size::new(4, 3))),
+        Point::zero(),
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    display.assert_pattern(&[
+        "   B", //
+        "    ", //
+        "RRRR", //
+    ]);
+}
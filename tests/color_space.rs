@@ -0,0 +1,126 @@
+use tinybmp::{CieXyz, ColorSpace, ColorSpaceInfo, IccProfile, RawBmp, RenderingIntent};
+
+const V5_HEADER: &[u8] = include_bytes!("./chessboard-8px-color-16bit.bmp");
+
+/// Appends profile data to a BMP file with a V5 header and updates the color space fields.
+fn with_profile(cs_type: &[u8; 4], profile: &[u8], size: u32) -> Vec<u8> {
+    let mut data = V5_HEADER.to_vec();
+    let offset = data.len() as u32 - 14;
+
+    data[70..74].copy_from_slice(cs_type);
+    data[126..130].copy_from_slice(&offset.to_le_bytes());
+    data[130..134].copy_from_slice(&size.to_le_bytes());
+    data.extend_from_slice(profile);
+
+    data
+}
+
+#[test]
+fn v4_header() {
+    let bmp = RawBmp::from_slice(include_bytes!("./chessboard-8px-1bit-inverted.bmp")).unwrap();
+
+    // The Z coordinates of the endpoints are set to 1.0 in 2.30 fixed point format.
+    assert_eq!(
+        bmp.header().color_space,
+        Some(ColorSpaceInfo {
+            color_space: ColorSpace::Srgb,
+            endpoints: [
+                CieXyz::new(0, 0, 1 << 30),
+                CieXyz::new(0, 0, 1 << 30),
+                CieXyz::new(0, 0, 1 << 30),
+            ],
+            gamma: [0; 3],
+            intent: None,
+            profile_offset: 0,
+            profile_size: 0,
+        })
+    );
+}
+
+#[test]
+fn v5_header() {
+    let bmp = RawBmp::from_slice(include_bytes!("./logo-indexed-8bpp-rle8.bmp")).unwrap();
+
+    let color_space = bmp.header().color_space.unwrap();
+    assert_eq!(color_space.color_space, ColorSpace::Srgb);
+    assert_eq!(
+        color_space.intent,
+        Some(RenderingIntent::RelativeColorimetric)
+    );
+}
+
+#[test]
+fn v5_header_without_channel_masks() {
+    let bmp = RawBmp::from_slice(include_bytes!("./chessboard-8px-24bit-v5.bmp")).unwrap();
+
+    assert_eq!(
+        bmp.header().color_space,
+        Some(ColorSpaceInfo {
+            color_space: ColorSpace::Srgb,
+            endpoints: [CieXyz::default(); 3],
+            gamma: [0; 3],
+            intent: Some(RenderingIntent::Perceptual),
+            profile_offset: 0,
+            profile_size: 0,
+        })
+    );
+}
+
+#[test]
+fn info_header() {
+    for data in [
+        include_bytes!("./logo-indexed-8bpp.bmp").as_slice(),
+        include_bytes!("./colors_rgb565_v2.bmp").as_slice(),
+        include_bytes!("./os2_core_8bpp.bmp").as_slice(),
+    ] {
+        let bmp = RawBmp::from_slice(data).unwrap();
+        assert_eq!(bmp.header().color_space, None);
+    }
+}
+
+#[test]
+fn embedded_profile() {
+    let data = with_profile(b"DEBM", b"icc profile", 11);
+    let bmp = RawBmp::from_slice(&data).unwrap();
+
+    let color_space = bmp.header().color_space.unwrap();
+    assert_eq!(color_space.color_space, ColorSpace::EmbeddedProfile);
+    assert_eq!(color_space.profile_size, 11);
+    assert_eq!(
+        bmp.icc_profile(),
+        Some(IccProfile::Embedded(b"icc profile"))
+    );
+
+    // The profile offset is relative to the DIB header.
+    let bmp = RawBmp::from_dib_slice(&data[14..]).unwrap();
+    assert_eq!(
+        bmp.icc_profile(),
+        Some(IccProfile::Embedded(b"icc profile"))
+    );
+}
+
+#[test]
+fn linked_profile() {
+    let data = with_profile(b"KNIL", b"sRGB.icc\0\0\0", 11);
+    let bmp = RawBmp::from_slice(&data).unwrap();
+
+    assert_eq!(
+        bmp.header().color_space.unwrap().color_space,
+        ColorSpace::LinkedProfile
+    );
+    assert_eq!(bmp.icc_profile(), Some(IccProfile::Linked(b"sRGB.icc")));
+}
+
+#[test]
+fn profile_out_of_bounds() {
+    let data = with_profile(b"DEBM", b"icc profile", 12);
+    let bmp = RawBmp::from_slice(&data).unwrap();
+
+    assert_eq!(bmp.icc_profile(), None);
+}
+
+#[test]
+fn no_profile() {
+    let bmp = RawBmp::from_slice(V5_HEADER).unwrap();
+    assert_eq!(bmp.icc_profile(), None);
+}
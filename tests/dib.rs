@@ -0,0 +1,70 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use tinybmp::{Bmp, RawBmp};
+
+const FILE_HEADER_SIZE: usize = 14;
+
+#[track_caller]
+fn assert_same_image(data: &[u8]) {
+    let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+    let dib = &data[FILE_HEADER_SIZE..];
+    let dib_bmp = Bmp::<Rgb888>::from_dib_slice(dib).unwrap();
+
+    let header = dib_bmp.as_raw().header();
+    assert_eq!(header.file_size as usize, dib.len());
+    assert_eq!(
+        header.image_data_start,
+        bmp.as_raw().header().image_data_start - FILE_HEADER_SIZE
+    );
+    assert_eq!(dib_bmp.as_raw().image_data(), bmp.as_raw().image_data());
+
+    assert!(dib_bmp.pixels().eq(bmp.pixels()));
+}
+
+#[test]
+fn info_header() {
+    assert_same_image(include_bytes!("./colors_rgb888_24bit.bmp"));
+}
+
+#[test]
+fn info_header_with_color_table() {
+    assert_same_image(include_bytes!("./logo-indexed-8bpp.bmp"));
+}
+
+#[test]
+fn info_header_with_channel_masks() {
+    assert_same_image(include_bytes!("./colors_rgb565_info.bmp"));
+}
+
+#[test]
+fn v4_header() {
+    assert_same_image(include_bytes!("./chessboard-8px-24bit.bmp"));
+}
+
+#[test]
+fn core_header() {
+    assert_same_image(include_bytes!("./os2_core_8bpp.bmp"));
+}
+
+#[test]
+fn rle_compressed() {
+    assert_same_image(include_bytes!("./rle8_delta.bmp"));
+}
+
+#[test]
+fn const_constructor() {
+    const DIB: &[u8] = include_bytes!("./colors_rgb888_24bit.bmp")
+        .split_at(FILE_HEADER_SIZE)
+        .1;
+    const BMP: RawBmp<'static> = match RawBmp::from_dib_slice(DIB) {
+        Ok(bmp) => bmp,
+        Err(_) => panic!("invalid DIB"),
+    };
+
+    assert_eq!(BMP.header().image_size, Size::new(4, 2));
+}
+
+#[test]
+fn file_header_is_not_allowed() {
+    assert!(RawBmp::from_dib_slice(include_bytes!("./colors_rgb888_24bit.bmp")).is_err());
+}
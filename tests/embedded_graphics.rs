@@ -1,11 +1,11 @@
 use embedded_graphics::{
-    image::{Image, ImageRawLE},
+    image::{GetPixel, Image, ImageRawLE},
     mock_display::{ColorMapping, MockDisplay},
     pixelcolor::{Bgr888, Gray8, Rgb555, Rgb565, Rgb888},
     prelude::*,
     primitives::Rectangle,
 };
-use tinybmp::{Bmp, RowOrder};
+use tinybmp::{Bmp, Bpp, DynamicRawColors, RawBmp, RowOrder};
 
 #[test]
 fn negative_top_left() {
@@ -78,6 +78,17 @@ fn colors_rgb565() {
     test_color_pattern::<Rgb565>(include_bytes!("./colors_rgb565.bmp"));
 }
 
+#[test]
+fn colors_rgb565_v2_header() {
+    test_color_pattern::<Rgb565>(include_bytes!("./colors_rgb565_v2.bmp"));
+}
+
+#[test]
+fn colors_rgb565_info_header() {
+    // The channel masks are stored after the 40 byte info header.
+    test_color_pattern::<Rgb565>(include_bytes!("./colors_rgb565_info.bmp"));
+}
+
 #[test]
 fn colors_rgb888_24bit() {
     test_color_pattern::<Rgb888>(include_bytes!("./colors_rgb888_24bit.bmp"));
@@ -88,6 +99,95 @@ fn colors_rgb888_32bit() {
     test_color_pattern::<Rgb888>(include_bytes!("./colors_rgb888_32bit.bmp"));
 }
 
+#[test]
+fn colors_xrgb4444() {
+    test_color_pattern::<Rgb888>(include_bytes!("./colors_xrgb4444.bmp"));
+}
+
+#[test]
+fn colors_xbgr8888() {
+    test_color_pattern::<Rgb888>(include_bytes!("./colors_xbgr8888.bmp"));
+}
+
+#[test]
+fn colors_rgb101010() {
+    test_color_pattern::<Rgb888>(include_bytes!("./colors_rgb101010.bmp"));
+}
+
+#[test]
+fn colors_rgb64() {
+    test_color_pattern::<Rgb888>(include_bytes!("./colors_rgb64.bmp"));
+}
+
+#[test]
+fn colors_rgb64_raw() {
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_rgb64.bmp")).unwrap();
+    assert_eq!(bmp.header().bpp, Bpp::Bits64);
+    assert!(bmp.has_alpha());
+
+    // The white pixel is brighter than the sRGB range and is clamped.
+    assert_eq!(bmp.pixel(Point::new(3, 1)), Some(0x00FFFFFF));
+    assert_eq!(
+        bmp.pixel_wide(Point::new(3, 1)),
+        Some(0x0000_3000_3000_3000)
+    );
+    assert_eq!(
+        bmp.pixel_wide(Point::new(1, 0)),
+        Some(0x2000_2000_0000_0000)
+    );
+    assert_eq!(bmp.pixel_wide(Point::new(4, 0)), None);
+
+    assert_eq!(bmp.alpha(Point::new(2, 1)), Some(128));
+    assert_eq!(bmp.alpha(Point::new(3, 1)), Some(0));
+
+    let colors: Vec<u32> = bmp.colors().collect();
+    assert_eq!(
+        colors[..4],
+        [0xFF000000, 0xFFFF0000, 0xFF00FF00, 0xFFFFFF00]
+    );
+
+    let DynamicRawColors::Bpp64(colors) = bmp.colors() else {
+        panic!("expected 64 bpp colors");
+    };
+    let colors: Vec<u64> = colors.collect();
+    assert_eq!(colors[0], 0x2000_0000_0000_0000);
+    assert_eq!(colors[4], 0x2000_0000_0000_2000);
+    assert_eq!(colors.len(), 8);
+}
+
+#[test]
+fn colors_gray2_2bpp() {
+    let data = include_bytes!("./colors_gray2_2bpp.bmp");
+    let expected = MockDisplay::from_pattern(&[
+        "05AF", //
+        "FA50", //
+    ]);
+
+    let bmp = Bmp::<Gray8>::from_slice(data).unwrap();
+    assert_eq!(bmp.as_raw().header().bpp, Bpp::Bits2);
+    draw_image(bmp).assert_eq(&expected);
+
+    for Pixel(p, color) in bmp.pixels() {
+        assert_eq!(Some(color), expected.get_pixel(p), "pixel at {p}");
+        assert_eq!(bmp.pixel(p), Some(color), "pixel at {p}");
+    }
+
+    let raw = RawBmp::from_slice(data).unwrap();
+    assert_eq!(raw.colors().collect::<Vec<_>>(), [0, 1, 2, 3, 3, 2, 1, 0]);
+    assert_eq!(raw.pixel(Point::new(1, 0)), Some(1));
+}
+
+#[test]
+fn colors_bitfields_pixels_and_getter() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_xrgb4444.bmp")).unwrap();
+    let expected = expected_image_color::<Rgb888>();
+
+    for Pixel(p, color) in bmp.pixels() {
+        assert_eq!(Some(color), expected.get_pixel(p), "pixel at {p}");
+        assert_eq!(bmp.pixel(p), Some(color), "pixel at {p}");
+    }
+}
+
 #[test]
 fn colors_grey8() {
     let bmp: Bmp<Gray8> = Bmp::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
@@ -0,0 +1,160 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::Rgb888,
+    prelude::*,
+};
+use tinybmp::{Bmp, Bpp, CompressionMethod, RawBmp};
+
+const JPEG: &[u8] = include_bytes!("./jpeg.bmp");
+const PNG_RGB: &[u8] = include_bytes!("./png_rgb.bmp");
+
+#[test]
+fn jpeg() {
+    let bmp = RawBmp::from_slice(JPEG).unwrap();
+
+    assert_eq!(bmp.header().compression_method, CompressionMethod::Jpeg);
+    assert_eq!(bmp.header().bpp, Bpp::Bits24);
+
+    let jpeg = bmp.embedded_image().unwrap();
+    assert!(jpeg.starts_with(&[0xFF, 0xD8]));
+    assert!(jpeg.ends_with(&[0xFF, 0xD9]));
+    assert_eq!(bmp.pixel(Point::zero()), None);
+}
+
+#[test]
+fn png() {
+    let bmp = RawBmp::from_slice(PNG_RGB).unwrap();
+
+    assert_eq!(bmp.header().compression_method, CompressionMethod::Png);
+    assert_eq!(bmp.header().image_size, Size::new(4, 5));
+    assert!(bmp.embedded_image().unwrap().starts_with(b"\x89PNG"));
+    assert_eq!(bmp.pixels().count(), 0);
+}
+
+#[test]
+fn no_embedded_image() {
+    let bmp = RawBmp::from_slice(include_bytes!("./chessboard-8px-24bit.bmp")).unwrap();
+
+    assert_eq!(bmp.embedded_image(), None);
+}
+
+#[test]
+fn embedded_images_are_not_drawn() {
+    for data in [JPEG, PNG_RGB] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        assert_eq!(bmp.pixel(Point::zero()), None);
+
+        let mut display = MockDisplay::<Rgb888>::new();
+        Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+        display.assert_pattern(&[]);
+    }
+}
+
+#[cfg(feature = "png")]
+mod decode {
+    use super::*;
+    use tinybmp::{PngError, PngImage};
+
+    const EXPECTED_PATTERN: &[&str] = &[
+        "RGBW", //
+        "GBWK", //
+        "BWKR", //
+        "WKRG", //
+        "KRGB", //
+    ];
+
+    fn decode<'a>(data: &'a [u8], buffer: &'a mut [u8]) -> PngImage<'a, Rgb888> {
+        let png = RawBmp::from_slice(data).unwrap().embedded_image().unwrap();
+        PngImage::decode(png, buffer).unwrap()
+    }
+
+    #[test]
+    fn draw() {
+        for data in [
+            PNG_RGB,
+            include_bytes!("./png_rgba.bmp").as_slice(),
+            include_bytes!("./png_indexed.bmp").as_slice(),
+        ] {
+            let mut buffer = [0; 85];
+            let image = decode(data, &mut buffer);
+
+            let mut display = MockDisplay::new();
+            Image::new(&image, Point::zero())
+                .draw(&mut display)
+                .unwrap();
+            display.assert_pattern(EXPECTED_PATTERN);
+        }
+    }
+
+    #[test]
+    fn pixel() {
+        let mut buffer = [0; 85];
+        let image = decode(PNG_RGB, &mut buffer);
+
+        assert_eq!(image.size(), Size::new(4, 5));
+        assert_eq!(image.pixel(Point::new(0, 0)), Some(Rgb888::RED));
+        assert_eq!(image.pixel(Point::new(3, 4)), Some(Rgb888::BLUE));
+        assert_eq!(image.pixel(Point::new(4, 0)), None);
+        assert_eq!(image.pixel(Point::new(0, -1)), None);
+        assert_eq!(image.alpha(Point::new(1, 1)), Some(255));
+        assert!(!image.has_alpha());
+    }
+
+    #[test]
+    fn alpha() {
+        let mut buffer = [0; 85];
+        let image = decode(include_bytes!("./png_rgba.bmp"), &mut buffer);
+
+        assert!(image.has_alpha());
+        assert_eq!(image.alpha(Point::new(0, 0)), Some(255));
+        assert_eq!(image.alpha(Point::new(1, 0)), Some(0));
+        assert_eq!(image.alpha(Point::new(1, 1)), Some(255));
+        assert_eq!(image.alpha(Point::new(4, 0)), None);
+    }
+
+    #[test]
+    fn palette_transparency() {
+        let mut buffer = [0; 85];
+        let image = decode(include_bytes!("./png_indexed.bmp"), &mut buffer);
+
+        // Only the first palette entry (black) is transparent.
+        assert!(image.has_alpha());
+        assert_eq!(image.alpha(Point::new(3, 1)), Some(0));
+        assert_eq!(image.alpha(Point::new(0, 0)), Some(255));
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let png = RawBmp::from_slice(PNG_RGB)
+            .unwrap()
+            .embedded_image()
+            .unwrap();
+
+        // 5 rows with 4 RGB pixels and a filter type byte.
+        assert_eq!(PngImage::<Rgb888>::buffer_len(png), Ok(65));
+        assert_eq!(
+            PngImage::<Rgb888>::decode(png, &mut [0; 64]),
+            Err(PngError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        let png = RawBmp::from_slice(PNG_RGB)
+            .unwrap()
+            .embedded_image()
+            .unwrap();
+
+        // Truncated IDAT chunks.
+        assert_eq!(
+            PngImage::<Rgb888>::decode(&png[..png.len() - 30], &mut [0; 85]),
+            Err(PngError::InvalidData)
+        );
+
+        assert_eq!(
+            PngImage::<Rgb888>::decode(&JPEG[54..], &mut [0; 85]),
+            Err(PngError::InvalidFileSignature)
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use embedded_graphics::{
+    image::GetPixel,
+    pixelcolor::{BinaryColor, Rgb565, Rgb888},
+    prelude::*,
+};
+use tinybmp::{
+    Bmp, Bpp, BufferTooSmall, ByteSink, ChannelMasks, CompressionMethod, EncodeError, Encoder,
+    RawBmp, RowOrder,
+};
+
+const COLORS: &[u8] = include_bytes!("./colors_rgb888_24bit.bmp");
+
+/// Byte sink which appends to a `Vec`.
+struct VecSink(Vec<u8>);
+
+impl ByteSink for VecSink {
+    type Error = core::convert::Infallible;
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.0.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+fn encode<I>(encoder: &Encoder, image: &I) -> Vec<u8>
+where
+    I: GetPixel + OriginDimensions,
+    I::Color: Into<Rgb888>,
+{
+    let mut sink = VecSink(Vec::new());
+    encoder.encode(image, &mut sink).unwrap();
+
+    let header = encoder.header(image.size()).unwrap();
+    assert_eq!(sink.0.len(), header.file_size as usize);
+
+    sink.0
+}
+
+fn assert_same_pixels<A, B>(a: &A, b: &B)
+where
+    A: GetPixel + OriginDimensions,
+    B: GetPixel<Color = A::Color> + OriginDimensions,
+    A::Color: core::fmt::Debug,
+{
+    assert_eq!(a.size(), b.size());
+    for p in a.bounding_box().points() {
+        assert_eq!(a.pixel(p), b.pixel(p), "pixel at {p}");
+    }
+}
+
+#[test]
+fn rgb_formats() {
+    let bmp = Bmp::<Rgb888>::from_slice(COLORS).unwrap();
+
+    let encoders = [
+        Encoder::new(Bpp::Bits16),
+        Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks::RGB565),
+        Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks {
+            red: 0x0F00,
+            green: 0x00F0,
+            blue: 0x000F,
+            alpha: 0xF000,
+        }),
+        Encoder::new(Bpp::Bits24),
+        Encoder::new(Bpp::Bits32),
+        Encoder::new(Bpp::Bits32).channel_masks(ChannelMasks::ARGB8888),
+        Encoder::new(Bpp::Bits24).row_order(RowOrder::TopDown),
+    ];
+
+    for encoder in encoders {
+        let data = encode(&encoder, &bmp);
+        let encoded = Bmp::<Rgb888>::from_slice(&data).unwrap();
+
+        assert_eq!(
+            encoded.as_raw().header(),
+            &encoder.header(bmp.size()).unwrap()
+        );
+        assert_same_pixels(&encoded, &bmp);
+    }
+}
+
+#[test]
+fn channel_masks_use_bitfields() {
+    let bmp = Bmp::<Rgb888>::from_slice(COLORS).unwrap();
+    let encoder = Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks::RGB565);
+
+    let data = encode(&encoder, &bmp);
+    let header = *RawBmp::from_slice(&data).unwrap().header();
+
+    assert_eq!(header.compression_method, CompressionMethod::Bitfields);
+    assert_eq!(header.channel_masks, Some(ChannelMasks::RGB565));
+}
+
+#[test]
+fn indexed_8bpp() {
+    let data = include_bytes!("./logo-indexed-8bpp.bmp");
+    let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+    let color_table = bmp.as_raw().color_table().unwrap();
+    let palette: Vec<Rgb888> = (0..color_table.len() as u32)
+        .map(|index| color_table.get(index).unwrap())
+        .collect();
+
+    let encoder = Encoder::new(Bpp::Bits8).color_table(&palette);
+    let encoded = encode(&encoder, &bmp);
+    let encoded = Bmp::<Rgb888>::from_slice(&encoded).unwrap();
+
+    assert_same_pixels(&encoded, &bmp);
+}
+
+#[test]
+fn indexed_1bpp_nearest_color() {
+    let bmp = Bmp::<Rgb888>::from_slice(COLORS).unwrap();
+    let palette = [Rgb888::BLACK, Rgb888::WHITE];
+
+    let encoder = Encoder::new(Bpp::Bits1).color_table(&palette);
+    let data = encode(&encoder, &bmp);
+    let encoded = Bmp::<Rgb888>::from_slice(&data).unwrap();
+
+    let expected = [
+        [Rgb888::BLACK, Rgb888::BLACK, Rgb888::BLACK, Rgb888::WHITE],
+        [Rgb888::BLACK, Rgb888::WHITE, Rgb888::WHITE, Rgb888::WHITE],
+    ];
+    for (y, row) in expected.iter().enumerate() {
+        for (x, color) in row.iter().enumerate() {
+            let p = Point::new(x as i32, y as i32);
+            assert_eq!(encoded.pixel(p), Some(*color), "pixel at {p}");
+        }
+    }
+}
+
+#[test]
+fn indexed_4bpp_binary_color() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./chessboard-8px-24bit.bmp")).unwrap();
+    let binary: Vec<BinaryColor> = bmp
+        .pixels()
+        .map(|Pixel(_, color)| BinaryColor::from(color == Rgb888::WHITE))
+        .collect();
+
+    let palette = [Rgb888::RED, Rgb888::BLACK, Rgb888::WHITE];
+    let encoder = Encoder::new(Bpp::Bits4).color_table(&palette);
+
+    let mut sink = VecSink(Vec::new());
+    encoder
+        .encode_pixels(bmp.size(), binary, &mut sink)
+        .unwrap();
+    let encoded = Bmp::<Rgb888>::from_slice(&sink.0).unwrap();
+
+    assert_eq!(encoded.as_raw().header().row_order, RowOrder::TopDown);
+    assert_same_pixels(&encoded, &bmp);
+}
+
+#[test]
+fn indexed_2bpp() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_gray2_2bpp.bmp")).unwrap();
+    let palette = [
+        Rgb888::BLACK,
+        Rgb888::new(0x55, 0x55, 0x55),
+        Rgb888::new(0xAA, 0xAA, 0xAA),
+        Rgb888::WHITE,
+    ];
+
+    let encoder = Encoder::new(Bpp::Bits2).color_table(&palette);
+    let data = encode(&encoder, &bmp);
+    let encoded = Bmp::<Rgb888>::from_slice(&data).unwrap();
+
+    assert_eq!(encoded.as_raw().header().bpp, Bpp::Bits2);
+    assert_same_pixels(&encoded, &bmp);
+}
+
+#[test]
+fn resolution() {
+    let bmp = Bmp::<Rgb888>::from_slice(COLORS).unwrap();
+
+    let encoder = Encoder::new(Bpp::Bits8)
+        .color_table(&[Rgb888::BLACK, Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE])
+        .dpi(Size::new(300, 150))
+        .colors_important(2);
+    let data = encode(&encoder, &bmp);
+
+    let header = *RawBmp::from_slice(&data).unwrap().header();
+    assert_eq!(header, encoder.header(bmp.size()).unwrap());
+    assert_eq!(header.pixels_per_meter, Size::new(11811, 5906));
+    assert_eq!(header.color_planes, 1);
+    assert_eq!(header.colors_important, 2);
+    assert_eq!(header.dpi(), Some(Size::new(300, 150)));
+
+    let (width, height) = header.physical_size_mm().unwrap();
+    assert!((width - 4.0 * 25.4 / 300.0).abs() < 0.001, "width: {width}");
+    assert!(
+        (height - 2.0 * 25.4 / 150.0).abs() < 0.001,
+        "height: {height}"
+    );
+}
+
+#[test]
+fn unspecified_resolution() {
+    let data = encode(
+        &Encoder::new(Bpp::Bits24),
+        &Bmp::<Rgb888>::from_slice(COLORS).unwrap(),
+    );
+
+    let header = *RawBmp::from_slice(&data).unwrap().header();
+    assert_eq!(header.pixels_per_meter, Size::zero());
+    assert_eq!(header.dpi(), None);
+    assert_eq!(header.physical_size_mm(), None);
+}
+
+#[test]
+fn encode_to_slice() {
+    let bmp = Bmp::<Rgb565>::from_slice(include_bytes!("./logo-rgb565.bmp")).unwrap();
+    let encoder = Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks::RGB565);
+
+    let mut buffer = vec![0xAA; 240 * 320 * 2 + 1024];
+    let length = encoder.encode_to_slice(&bmp, &mut buffer).unwrap();
+    let encoded = Bmp::<Rgb565>::from_slice(&buffer[..length]).unwrap();
+
+    assert_eq!(encoded.as_raw().image_data(), bmp.as_raw().image_data());
+}
+
+#[test]
+fn encode_drawable_to_slice() {
+    let rle = Bmp::<Rgb888>::from_slice(include_bytes!("./logo-indexed-4bpp-rle4.bmp")).unwrap();
+    let expected = Bmp::<Rgb888>::from_slice(include_bytes!("./logo-indexed-4bpp.bmp")).unwrap();
+
+    let encoder = Encoder::new(Bpp::Bits24);
+    let mut buffer = vec![0; encoder.header(rle.size()).unwrap().file_size as usize];
+    let length = encoder.encode_drawable_to_slice(&rle, &mut buffer).unwrap();
+    assert_eq!(length, buffer.len());
+
+    let encoded = Bmp::<Rgb888>::from_slice(&buffer).unwrap();
+    assert_same_pixels(&encoded, &expected);
+}
+
+#[test]
+fn buffer_too_small() {
+    let bmp = Bmp::<Rgb888>::from_slice(COLORS).unwrap();
+    let encoder = Encoder::new(Bpp::Bits24);
+    let file_size = encoder.header(bmp.size()).unwrap().file_size as usize;
+
+    let mut buffer = vec![0; file_size - 1];
+    assert_eq!(
+        encoder.encode_to_slice(&bmp, &mut buffer),
+        Err(EncodeError::Write(BufferTooSmall))
+    );
+    assert_eq!(
+        encoder.encode_drawable_to_slice(&bmp, &mut buffer),
+        Err(EncodeError::Write(BufferTooSmall))
+    );
+}
+
+#[test]
+fn unsupported_formats() {
+    let size = Size::new(1, 1);
+    let palette = [Rgb888::BLACK; 3];
+
+    assert_eq!(
+        Encoder::new(Bpp::Bits8).header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits1).color_table(&palette).header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits24).color_table(&palette).header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits24)
+            .channel_masks(ChannelMasks::RGB888)
+            .header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits16)
+            .channel_masks(ChannelMasks::RGB888)
+            .header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits64).header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits24).header(Size::new(0, 1)),
+        Err(EncodeError::InvalidImageDimensions)
+    );
+}
+
+#[test]
+fn rle_compression() {
+    let files: [&[u8]; 2] = [
+        include_bytes!("./logo-indexed-8bpp.bmp"),
+        include_bytes!("./logo-indexed-4bpp.bmp"),
+    ];
+
+    for data in files {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        let color_table = bmp.as_raw().color_table().unwrap();
+        let palette: Vec<Rgb888> = (0..color_table.len() as u32)
+            .map(|index| color_table.get(index).unwrap())
+            .collect();
+
+        let bpp = bmp.as_raw().header().bpp;
+        let encoder = Encoder::new(bpp)
+            .color_table(&palette)
+            .rle_compression(true);
+        let max_file_size = encoder.header(bmp.size()).unwrap().file_size as usize;
+
+        let mut buffer = vec![0; max_file_size];
+        let length = encoder.encode_to_slice(&bmp, &mut buffer).unwrap();
+        assert!(length < data.len());
+
+        let encoded = Bmp::<Rgb888>::from_slice(&buffer[..length]).unwrap();
+        let header = encoded.as_raw().header();
+        assert_eq!(header.file_size as usize, length);
+        let expected_compression = match bpp {
+            Bpp::Bits4 => CompressionMethod::Rle4,
+            _ => CompressionMethod::Rle8,
+        };
+        assert_eq!(header.compression_method, expected_compression);
+
+        // Decode the compressed image by drawing it into an uncompressed image.
+        let decoder = Encoder::new(Bpp::Bits24);
+        let mut decoded = vec![0; decoder.header(bmp.size()).unwrap().file_size as usize];
+        decoder
+            .encode_drawable_to_slice(&encoded, &mut decoded)
+            .unwrap();
+        let decoded = Bmp::<Rgb888>::from_slice(&decoded).unwrap();
+
+        assert_same_pixels(&decoded, &bmp);
+    }
+}
+
+#[test]
+fn rle_unsupported_formats() {
+    let size = Size::new(1, 1);
+    let palette = [Rgb888::BLACK; 2];
+
+    assert_eq!(
+        Encoder::new(Bpp::Bits1)
+            .color_table(&palette)
+            .rle_compression(true)
+            .header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        Encoder::new(Bpp::Bits8)
+            .color_table(&palette)
+            .rle_compression(true)
+            .row_order(RowOrder::TopDown)
+            .header(size),
+        Err(EncodeError::UnsupportedFormat)
+    );
+
+    let encoder = Encoder::new(Bpp::Bits8)
+        .color_table(&palette)
+        .rle_compression(true);
+    assert_eq!(
+        encoder.encode_pixels(size, [Rgb888::BLACK], &mut VecSink(Vec::new())),
+        Err(EncodeError::UnsupportedFormat)
+    );
+    assert_eq!(
+        encoder.framebuffer::<Rgb888>(size, &mut [0; 1024]).err(),
+        Some(EncodeError::UnsupportedFormat)
+    );
+}
@@ -0,0 +1,228 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+    primitives::{Circle, PrimitiveStyle, Rectangle},
+};
+use tinybmp::{
+    Bmp, BmpFramebuffer, Bpp, ChannelMasks, EncodeError, Encoder, IccProfile, ParseError, RawBmp,
+    RawBmpMut, RowOrder,
+};
+
+const PALETTE: [Rgb888; 8] = [
+    Rgb888::BLACK,
+    Rgb888::RED,
+    Rgb888::GREEN,
+    Rgb888::BLUE,
+    Rgb888::YELLOW,
+    Rgb888::MAGENTA,
+    Rgb888::CYAN,
+    Rgb888::WHITE,
+];
+
+fn draw_shapes<D: DrawTarget<Color = Rgb888>>(target: &mut D) -> Result<(), D::Error> {
+    target.clear(Rgb888::BLUE)?;
+
+    Rectangle::new(Point::new(1, 2), Size::new(5, 3))
+        .into_styled(PrimitiveStyle::with_fill(Rgb888::RED))
+        .draw(target)?;
+
+    Circle::new(Point::new(4, 3), 7)
+        .into_styled(PrimitiveStyle::with_stroke(Rgb888::YELLOW, 1))
+        .draw(target)
+}
+
+#[test]
+fn draw_into_all_formats() {
+    let mut expected = MockDisplay::new();
+    expected.set_allow_overdraw(true);
+    draw_shapes(&mut expected.clipped(&Rectangle::new(Point::zero(), Size::new(11, 10)))).unwrap();
+
+    let encoders = [
+        Encoder::new(Bpp::Bits1).color_table(&[Rgb888::BLACK, Rgb888::WHITE]),
+        Encoder::new(Bpp::Bits4).color_table(&PALETTE),
+        Encoder::new(Bpp::Bits8).color_table(&PALETTE),
+        Encoder::new(Bpp::Bits16),
+        Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks::RGB565),
+        Encoder::new(Bpp::Bits24),
+        Encoder::new(Bpp::Bits24).row_order(RowOrder::TopDown),
+        Encoder::new(Bpp::Bits32),
+        Encoder::new(Bpp::Bits32).channel_masks(ChannelMasks::ARGB8888),
+    ];
+
+    for encoder in encoders {
+        let mut buffer = [0; 1024];
+        let mut framebuffer = encoder
+            .framebuffer::<Rgb888>(Size::new(11, 10), &mut buffer)
+            .unwrap();
+        draw_shapes(&mut framebuffer).unwrap();
+
+        let file_size = framebuffer.as_raw().header().file_size as usize;
+        let bmp = Bmp::<Rgb888>::from_slice(&buffer[..file_size]).unwrap();
+
+        for p in bmp.bounding_box().points() {
+            let expected = expected.get_pixel(p).unwrap();
+            let expected = if encoder.header(bmp.size()).unwrap().bpp == Bpp::Bits1 {
+                // Blue and red are closer to black than to white.
+                if expected == Rgb888::YELLOW {
+                    Rgb888::WHITE
+                } else {
+                    Rgb888::BLACK
+                }
+            } else {
+                expected
+            };
+
+            assert_eq!(bmp.pixel(p), Some(expected), "pixel at {p}");
+        }
+    }
+}
+
+#[test]
+fn draw_into_existing_file() {
+    let mut data = *include_bytes!("./chessboard-8px-24bit.bmp");
+    let original = data;
+
+    let mut framebuffer = BmpFramebuffer::<Rgb888>::from_slice(&mut data).unwrap();
+    assert_eq!(framebuffer.size(), Size::new(8, 8));
+
+    Pixel(Point::new(0, 0), Rgb888::RED)
+        .draw(&mut framebuffer)
+        .unwrap();
+    Pixel(Point::new(7, 7), Rgb888::GREEN)
+        .draw(&mut framebuffer)
+        .unwrap();
+    // Pixels outside the image are ignored.
+    Pixel(Point::new(8, 0), Rgb888::GREEN)
+        .draw(&mut framebuffer)
+        .unwrap();
+
+    let bmp = framebuffer.as_bmp();
+    assert_eq!(bmp.pixel(Point::new(0, 0)), Some(Rgb888::RED));
+    assert_eq!(bmp.pixel(Point::new(7, 7)), Some(Rgb888::GREEN));
+
+    // The file is stored bottom-up, which means that the top left pixel is in the last row.
+    let image_data_start = RawBmp::from_slice(&original)
+        .unwrap()
+        .header()
+        .image_data_start;
+    assert_eq!(&data[image_data_start + 7 * 3..][..3], &[0x00, 0xFF, 0x00]);
+    assert_eq!(&data[data.len() - 24..][..3], &[0x00, 0x00, 0xFF]);
+
+    let changed = data
+        .iter()
+        .zip(original.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert!(changed <= 6);
+}
+
+#[test]
+fn draw_image_into_framebuffer() {
+    let logo = Bmp::<Rgb565>::from_slice(include_bytes!("./logo-rgb565.bmp")).unwrap();
+
+    let encoder = Encoder::new(Bpp::Bits16).channel_masks(ChannelMasks::RGB565);
+    let header = encoder.header(logo.size()).unwrap();
+    let mut buffer = vec![0; header.file_size as usize];
+
+    let mut framebuffer = encoder
+        .framebuffer::<Rgb565>(logo.size(), &mut buffer)
+        .unwrap();
+    Image::new(&logo, Point::zero())
+        .draw(&mut framebuffer)
+        .unwrap();
+
+    assert_eq!(
+        framebuffer.as_raw().image_data(),
+        logo.as_raw().image_data()
+    );
+}
+
+#[test]
+fn set_raw_pixels() {
+    let mut data = *include_bytes!("./chessboard-8px-1bit.bmp");
+    let mut raw = RawBmpMut::from_slice(&mut data).unwrap();
+
+    let p = Point::new(1, 0);
+    let value = raw.pixel(p).unwrap();
+    raw.set_pixel(p, value ^ 1);
+    assert_eq!(raw.pixel(p), Some(value ^ 1));
+    assert_eq!(raw.as_raw().pixel(Point::new(2, 0)), Some(value ^ 1));
+
+    raw.image_data_mut().fill(0xFF);
+    assert_eq!(raw.pixel(Point::new(5, 5)), Some(1));
+}
+
+#[test]
+fn rle_is_unsupported() {
+    let mut data = *include_bytes!("./logo-indexed-8bpp-rle8.bmp");
+
+    assert_eq!(
+        BmpFramebuffer::<Rgb888>::from_slice(&mut data),
+        Err(ParseError::UnsupportedCompressionMethod(1))
+    );
+}
+
+#[test]
+fn bpp64_is_unsupported() {
+    let mut data = *include_bytes!("./colors_rgb64.bmp");
+
+    assert_eq!(
+        RawBmpMut::from_slice(&mut data),
+        Err(ParseError::UnsupportedBpp(64))
+    );
+}
+
+#[test]
+fn set_raw_pixels_2bpp() {
+    let mut data = *include_bytes!("./colors_gray2_2bpp.bmp");
+    let mut bmp = RawBmpMut::from_slice(&mut data).unwrap();
+
+    bmp.set_pixel(Point::new(1, 0), 3);
+    bmp.set_pixel(Point::new(2, 1), 0);
+
+    let colors: Vec<u32> = bmp.as_raw().colors().collect();
+    assert_eq!(colors, [0, 3, 2, 3, 3, 2, 0, 0]);
+}
+
+#[test]
+fn buffer_too_small() {
+    let mut buffer = [0; 64];
+
+    assert_eq!(
+        Encoder::new(Bpp::Bits24)
+            .framebuffer::<Rgb888>(Size::new(8, 8), &mut buffer)
+            .err(),
+        Some(EncodeError::Write(tinybmp::BufferTooSmall))
+    );
+}
+
+#[test]
+fn icc_profile() {
+    let mut data = include_bytes!("./chessboard-8px-color-16bit.bmp").to_vec();
+    let image_data_start = u32::from_le_bytes(data[10..14].try_into().unwrap());
+    let profile_offset = data.len() as u32 - 14;
+
+    // Append an embedded profile to the V5 header.
+    data[70..74].copy_from_slice(b"DEBM");
+    data[126..130].copy_from_slice(&profile_offset.to_le_bytes());
+    data[130..134].copy_from_slice(&4u32.to_le_bytes());
+    data.extend_from_slice(b"icc\0");
+
+    let mut framebuffer = BmpFramebuffer::<Rgb565>::from_slice(&mut data).unwrap();
+    framebuffer.clear(Rgb565::RED).unwrap();
+    assert_eq!(
+        framebuffer.as_raw().as_raw().icc_profile(),
+        Some(IccProfile::Embedded(b"icc\0"))
+    );
+    assert_eq!(
+        framebuffer.as_bmp().as_raw().icc_profile(),
+        Some(IccProfile::Embedded(b"icc\0"))
+    );
+
+    // Profiles which overlap the image data are ignored.
+    data[126..130].copy_from_slice(&(image_data_start - 14).to_le_bytes());
+    let raw = RawBmpMut::from_slice(&mut data).unwrap();
+    assert_eq!(raw.as_raw().icc_profile(), None);
+}
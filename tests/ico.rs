@@ -0,0 +1,227 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bpp, Ico, IcoType, ParseError};
+
+const ICON: &[u8] = include_bytes!("./icon.ico");
+const CURSOR: &[u8] = include_bytes!("./cursor.cur");
+
+const PALETTE: [Rgb888; 5] = [
+    Rgb888::BLACK,
+    Rgb888::RED,
+    Rgb888::GREEN,
+    Rgb888::BLUE,
+    Rgb888::WHITE,
+];
+
+/// Returns the expected color of a pixel in the test icons.
+fn expected_pixel(p: Point) -> Option<Rgb888> {
+    if p.x + p.y < 3 {
+        None
+    } else {
+        Some(PALETTE[((p.x / 4 + p.y / 4) % 4 + 1) as usize])
+    }
+}
+
+fn expected_display(size: Size) -> MockDisplay<Rgb888> {
+    let mut display = MockDisplay::new();
+    display.set_allow_out_of_bounds_drawing(true);
+    display
+        .draw_iter(
+            Rectangle::new(Point::zero(), size)
+                .points()
+                .filter_map(|p| expected_pixel(p).map(|color| Pixel(p, color))),
+        )
+        .unwrap();
+
+    display
+}
+
+#[test]
+fn entries() {
+    let ico = Ico::from_slice(ICON).unwrap();
+
+    assert_eq!(ico.ico_type(), IcoType::Icon);
+    assert_eq!(ico.len(), 4);
+
+    let entries: Vec<_> = ico
+        .entries()
+        .map(|entry| (entry.size(), entry.bpp(), entry.is_png(), entry.hotspot()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (Size::new(16, 16), 4, false, None),
+            (Size::new(32, 32), 8, false, None),
+            (Size::new(16, 16), 32, false, None),
+            (Size::new(48, 48), 32, true, None),
+        ]
+    );
+    assert_eq!(ico.entry(0).unwrap().color_count(), 16);
+    assert_eq!(ico.entry(4), None);
+    assert_eq!(ico.entry(usize::MAX / 16), None);
+}
+
+#[test]
+fn best_match() {
+    let ico = Ico::from_slice(ICON).unwrap();
+
+    let entry = ico.best_match(Size::new(16, 16)).unwrap();
+    assert_eq!((entry.size(), entry.bpp()), (Size::new(16, 16), 32));
+
+    let entry = ico.best_match(Size::new(30, 30)).unwrap();
+    assert_eq!((entry.size(), entry.bpp()), (Size::new(32, 32), 8));
+
+    let entry = ico.best_match(Size::new(64, 64)).unwrap();
+    assert!(entry.is_png());
+}
+
+#[test]
+fn draw_with_and_mask() {
+    let ico = Ico::from_slice(ICON).unwrap();
+
+    for index in 0..3 {
+        let entry = ico.entry(index).unwrap();
+        let image = entry.image::<Rgb888>().unwrap();
+        assert_eq!(image.size(), entry.size());
+
+        let mut display = MockDisplay::new();
+        display.set_allow_out_of_bounds_drawing(true);
+        Image::new(&image, Point::zero())
+            .draw(&mut display)
+            .unwrap();
+        display.assert_eq(&expected_display(entry.size()));
+
+        for p in image.bounding_box().points() {
+            assert_eq!(image.pixel(p), expected_pixel(p), "pixel at {p}");
+        }
+    }
+}
+
+#[test]
+fn draw_sub_image() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let area = Rectangle::new(Point::new(1, 0), Size::new(6, 5));
+
+    for index in 0..3 {
+        let image = ico.entry(index).unwrap().image::<Rgb888>().unwrap();
+
+        let mut display = MockDisplay::new();
+        Image::new(&image.sub_image(&area), Point::zero())
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        expected
+            .draw_iter(
+                area.points()
+                    .filter_map(|p| expected_pixel(p).map(|color| Pixel(p - area.top_left, color))),
+            )
+            .unwrap();
+        display.assert_eq(&expected);
+    }
+}
+
+#[test]
+fn bmp_ignores_and_mask() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let bmp = ico.entry(0).unwrap().bmp::<Rgb888>().unwrap();
+
+    assert_eq!(bmp.as_raw().header().bpp, Bpp::Bits4);
+    assert_eq!(bmp.size(), Size::new(16, 16));
+    assert_eq!(bmp.pixel(Point::new(0, 0)), Some(Rgb888::RED));
+}
+
+#[test]
+fn alpha_channel() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let bmp = ico.entry(2).unwrap().bmp::<Rgb888>().unwrap();
+
+    assert!(bmp.as_raw().has_alpha());
+    assert_eq!(bmp.alpha(Point::new(0, 0)), Some(0));
+    assert_eq!(bmp.alpha(Point::new(5, 5)), Some(255));
+}
+
+#[test]
+fn zero_alpha_channel_uses_and_mask() {
+    let mut data = ICON.to_vec();
+
+    // Replace the alpha channel of the 32 bpp entry by an AND mask, like in legacy icons.
+    let entry = 6 + 2 * 16;
+    let offset = u32::from_le_bytes(data[entry + 12..entry + 16].try_into().unwrap()) as usize;
+    let image_data = offset + 40;
+    let and_mask = image_data + 16 * 16 * 4;
+    for index in 0..16 * 16 {
+        let alpha = image_data + index * 4 + 3;
+        if data[alpha] == 0 {
+            // Each row of the AND mask is padded to 4 bytes.
+            data[and_mask + index / 16 * 4 + index % 16 / 8] |= 0x80 >> (index % 8);
+        }
+        data[alpha] = 0;
+    }
+
+    let ico = Ico::from_slice(&data).unwrap();
+    let image = ico.entry(2).unwrap().image::<Rgb888>().unwrap();
+    assert!(!image.as_bmp().as_raw().has_alpha());
+
+    let mut display = MockDisplay::new();
+    Image::new(&image, Point::zero())
+        .draw(&mut display)
+        .unwrap();
+    display.assert_eq(&expected_display(image.size()));
+}
+
+#[test]
+fn png_entry() {
+    let ico = Ico::from_slice(ICON).unwrap();
+    let entry = ico.entry(3).unwrap();
+
+    assert_eq!(
+        entry.raw_bmp(),
+        Err(ParseError::UnsupportedCompressionMethod(5))
+    );
+    assert!(entry.data().starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn cursor_hotspot() {
+    let cur = Ico::from_slice(CURSOR).unwrap();
+
+    assert_eq!(cur.ico_type(), IcoType::Cursor);
+
+    let entry = cur.entry(0).unwrap();
+    assert_eq!(entry.hotspot(), Some(Point::new(3, 5)));
+    assert_eq!(entry.bpp(), 4);
+
+    let image = entry.image::<Rgb888>().unwrap();
+    for p in image.bounding_box().points() {
+        assert_eq!(image.pixel(p), expected_pixel(p), "pixel at {p}");
+    }
+}
+
+#[test]
+fn invalid_header() {
+    let mut data = ICON.to_vec();
+    data[2] = 3;
+    assert_eq!(
+        Ico::from_slice(&data),
+        Err(ParseError::InvalidFileSignature([3, 0]))
+    );
+
+    assert!(matches!(
+        Ico::from_slice(include_bytes!("./chessboard-8px-1bit.bmp")),
+        Err(ParseError::InvalidFileSignature(_))
+    ));
+}
+
+#[test]
+fn truncated_file() {
+    assert_eq!(
+        Ico::from_slice(&ICON[..ICON.len() - 1]),
+        Err(ParseError::UnexpectedEndOfFile)
+    );
+}
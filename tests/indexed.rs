@@ -0,0 +1,156 @@
+use core::cell::Cell;
+
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::{BinaryColor, Gray8, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Bmp, RawBmp};
+
+#[test]
+fn draw_indexed_binary_color() {
+    for (data, on_index) in [
+        (include_bytes!("./chessboard-8px-1bit.bmp").as_slice(), 1),
+        (
+            include_bytes!("./chessboard-8px-1bit-inverted.bmp").as_slice(),
+            0,
+        ),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+        let mut display = MockDisplay::new();
+        bmp.draw_indexed(&mut display, |index| BinaryColor::from(index == on_index))
+            .unwrap();
+
+        display.assert_pattern(&[
+            "##..##..", //
+            "##..##..", //
+            "..##..##", //
+            "..##..##", //
+            "##..##..", //
+            "##..##..", //
+            "..##..##", //
+            "..##..##", //
+        ]);
+    }
+}
+
+#[test]
+fn draw_indexed_raw_indices() {
+    for data in [
+        include_bytes!("./logo-indexed-1bpp.bmp").as_slice(),
+        include_bytes!("./colors_gray2_2bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-4bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        let raw = RawBmp::from_slice(data).unwrap();
+
+        let mut display = MockDisplay::new();
+        display.set_allow_out_of_bounds_drawing(true);
+        bmp.draw_indexed(&mut display, Gray8::new).unwrap();
+
+        let area = display.bounding_box().intersection(&bmp.bounding_box());
+        for p in area.points() {
+            let index = raw.pixel(p).unwrap() as u8;
+            assert_eq!(
+                display.get_pixel(p),
+                Some(Gray8::new(index)),
+                "pixel at {p}"
+            );
+        }
+    }
+}
+
+#[test]
+fn draw_indexed_rle() {
+    for data in [
+        include_bytes!("./rle8_delta.bmp").as_slice(),
+        include_bytes!("./rle4_delta.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+        let calls = Cell::new(0);
+        let mut display = MockDisplay::new();
+        bmp.draw_indexed(&mut display, |index| {
+            calls.set(calls.get() + 1);
+            Gray8::new(index * 0x11)
+        })
+        .unwrap();
+
+        // Undefined pixels aren't drawn.
+        display.assert_pattern(&[
+            "404   ", //
+            "    33", //
+            "22    ", //
+            "111111", //
+        ]);
+
+        // `map` is only called once for each defined pixel.
+        assert_eq!(calls.get(), 13);
+    }
+}
+
+#[test]
+fn missing_color_table_entries() {
+    let mut data = *include_bytes!("./chessboard-8px-1bit.bmp");
+
+    // Only keep the first (black) color table entry.
+    data[46] = 1;
+
+    let bmp = Bmp::<Rgb888>::from_slice(&data).unwrap();
+    assert_eq!(bmp.as_raw().color_table().unwrap().len(), 1);
+
+    // Indices which are outside the color table are drawn as black.
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+    display.assert_pattern(&["KKKKKKKK"; 8]);
+
+    for Pixel(p, color) in bmp.pixels() {
+        assert_eq!(color, Rgb888::BLACK);
+        assert_eq!(bmp.pixel(p), Some(Rgb888::BLACK), "pixel at {p}");
+    }
+}
+
+#[test]
+fn draw_indexed_not_indexed() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+
+    let mut display = MockDisplay::<BinaryColor>::new();
+    bmp.draw_indexed(&mut display, |_| BinaryColor::On).unwrap();
+
+    display.assert_pattern(&[]);
+}
+
+#[test]
+fn grayscale_color_table() {
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
+    let color_table = bmp.color_table().unwrap();
+
+    assert!(color_table.is_grayscale());
+    assert_eq!(color_table.get_gray8(0x80), Some(Gray8::new(0x80)));
+    assert_eq!(color_table.get_gray8(256), None);
+
+    let bmp = RawBmp::from_slice(include_bytes!("./colors_8bpp_indexed.bmp")).unwrap();
+    assert!(!bmp.color_table().unwrap().is_grayscale());
+}
+
+#[test]
+fn draw_gray8() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_grey8.bmp")).unwrap();
+
+    let mut display = MockDisplay::new();
+    bmp.draw_gray8(&mut display).unwrap();
+    display.assert_pattern(&["08F"]);
+
+    // Images without a grayscale color table are converted from RGB.
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+    let expected = Bmp::<Gray8>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+
+    let mut display = MockDisplay::new();
+    bmp.draw_gray8(&mut display).unwrap();
+    for Pixel(p, color) in expected.pixels() {
+        assert_eq!(display.get_pixel(p), Some(color), "pixel at {p}");
+    }
+}
@@ -0,0 +1,80 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use tinybmp::{Bmp, Bpp, ParseError};
+
+fn pixels(data: &[u8]) -> Vec<Pixel<Rgb888>> {
+    Bmp::<Rgb888>::from_slice(data).unwrap().pixels().collect()
+}
+
+#[test]
+fn core_header_8bpp() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./os2_core_8bpp.bmp")).unwrap();
+
+    let color_table = bmp.as_raw().color_table().unwrap();
+    assert_eq!(color_table.len(), 256);
+    assert_eq!(bmp.as_raw().header().image_size, Size::new(4, 6));
+
+    assert_eq!(
+        pixels(include_bytes!("./os2_core_8bpp.bmp")),
+        pixels(include_bytes!("./colors_8bpp_indexed.bmp"))
+    );
+}
+
+#[test]
+fn core_header_24bpp() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./os2_core_24bpp.bmp")).unwrap();
+
+    assert_eq!(bmp.as_raw().header().bpp, Bpp::Bits24);
+    assert!(bmp.as_raw().color_table().is_none());
+
+    assert_eq!(
+        pixels(include_bytes!("./os2_core_24bpp.bmp")),
+        pixels(include_bytes!("./chessboard-8px-24bit.bmp"))
+    );
+}
+
+#[test]
+fn os2_v2_header() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./os2_v2_8bpp.bmp")).unwrap();
+
+    assert_eq!(bmp.as_raw().color_table().unwrap().len(), 21);
+
+    assert_eq!(
+        pixels(include_bytes!("./os2_v2_8bpp.bmp")),
+        pixels(include_bytes!("./colors_8bpp_indexed.bmp"))
+    );
+}
+
+#[test]
+fn os2_v2_huffman_compression() {
+    let mut data = *include_bytes!("./os2_v2_8bpp.bmp");
+    data[0x1E..0x1E + 4].copy_from_slice(&3u32.to_le_bytes());
+
+    assert_eq!(
+        Bmp::<Rgb888>::from_slice(&data),
+        Err(ParseError::UnsupportedCompressionMethod(3))
+    );
+}
+
+#[test]
+fn core_header_invalid_dimensions() {
+    let mut data = *include_bytes!("./os2_core_24bpp.bmp");
+    data[0x12..0x12 + 2].copy_from_slice(&0u16.to_le_bytes());
+
+    assert_eq!(
+        Bmp::<Rgb888>::from_slice(&data),
+        Err(ParseError::InvalidImageDimensions)
+    );
+}
+
+#[test]
+fn core_header_unsupported_bpp() {
+    for bpp in [2u16, 16, 32, 64] {
+        let mut data = *include_bytes!("./os2_core_24bpp.bmp");
+        data[0x18..0x18 + 2].copy_from_slice(&bpp.to_le_bytes());
+
+        assert_eq!(
+            Bmp::<Rgb888>::from_slice(&data),
+            Err(ParseError::UnsupportedBpp(bpp))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use core::cell::Cell;
+
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::Bmp;
+
+const CHESSBOARD: &[u8] = include_bytes!("./chessboard-8px-1bit.bmp");
+const RLE4_DELTA: &[u8] = include_bytes!("./rle4_delta.bmp");
+
+#[test]
+fn with_palette() {
+    let bmp = Bmp::<Rgb888>::from_slice(CHESSBOARD).unwrap();
+    let palette = [Rgb888::RED, Rgb888::BLUE];
+    let bmp = bmp.with_palette(&palette);
+    assert_eq!(bmp.palette(), &palette);
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+    display.assert_pattern(&[
+        "BBRRBBRR", //
+        "BBRRBBRR", //
+        "RRBBRRBB", //
+        "RRBBRRBB", //
+        "BBRRBBRR", //
+        "BBRRBBRR", //
+        "RRBBRRBB", //
+        "RRBBRRBB", //
+    ]);
+
+    assert_eq!(bmp.pixel(Point::new(0, 0)), Some(Rgb888::BLUE));
+    assert_eq!(bmp.pixel(Point::new(2, 0)), Some(Rgb888::RED));
+    assert_eq!(bmp.pixel(Point::new(8, 0)), None);
+}
+
+#[test]
+fn partial_palette() {
+    let bmp = Bmp::<Rgb888>::from_slice(RLE4_DELTA).unwrap();
+    let bmp = bmp.with_palette(&[Rgb888::YELLOW]);
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+    // Only the first color table entry is replaced.
+    display.assert_pattern(&[
+        "WYW   ", //
+        "    BB", //
+        "GG    ", //
+        "RRRRRR", //
+    ]);
+
+    assert_eq!(bmp.pixel(Point::new(1, 0)), Some(Rgb888::YELLOW));
+    assert_eq!(bmp.pixel(Point::new(0, 3)), Some(Rgb888::RED));
+    assert_eq!(bmp.pixel(Point::new(3, 0)), None);
+}
+
+#[test]
+fn sub_image() {
+    let bmp = Bmp::<Rgb888>::from_slice(RLE4_DELTA).unwrap();
+    let bmp = bmp.with_palette(&[Rgb888::YELLOW, Rgb888::CYAN]);
+
+    let mut display = MockDisplay::new();
+    Image::new(
+        &bmp.sub_image(&Rectangle::new(Point::new(1, 2), Size::new(3, 2))),
+        Point::zero(),
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    display.assert_pattern(&[
+        "G  ", //
+        "CCC", //
+    ]);
+}
+
+#[test]
+fn not_indexed() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+
+    let mut display = MockDisplay::new();
+    Image::new(&bmp.with_palette(&[Rgb888::WHITE]), Point::zero())
+        .draw(&mut display)
+        .unwrap();
+
+    display.assert_pattern(&[
+        "KRGY", //
+        "BMCW", //
+    ]);
+}
+
+#[test]
+fn draw_with_palette() {
+    let bmp = Bmp::<Rgb888>::from_slice(RLE4_DELTA).unwrap();
+
+    let calls = Cell::new(0);
+    let mut display = MockDisplay::new();
+    bmp.draw_with_palette(&mut display, |index| {
+        calls.set(calls.get() + 1);
+        Rgb888::new(index, 0, 0)
+    })
+    .unwrap();
+
+    // The palette is only evaluated once for each color index in a 4 bpp image.
+    assert_eq!(calls.get(), 16);
+
+    for p in bmp.bounding_box().points() {
+        let expected = bmp
+            .as_raw()
+            .pixel(p)
+            .map(|index| Rgb888::new(index as u8, 0, 0));
+        assert_eq!(display.get_pixel(p), expected, "pixel at {p}");
+    }
+}
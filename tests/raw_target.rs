@@ -0,0 +1,180 @@
+use core::convert::Infallible;
+
+use embedded_graphics::{
+    pixelcolor::{raw::RawU16, Rgb565},
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, RawDrawTarget, RawFormat};
+
+/// Draw target which records the areas of all raw transfers.
+#[derive(Debug)]
+struct RawDisplay {
+    pixels: Vec<Option<Rgb565>>,
+    raw_transfers: Vec<Rectangle>,
+}
+
+impl RawDisplay {
+    const SIZE: Size = Size::new(256, 352);
+
+    fn new() -> Self {
+        Self {
+            pixels: vec![None; Self::SIZE.width as usize * Self::SIZE.height as usize],
+            raw_transfers: Vec::new(),
+        }
+    }
+
+    fn set_pixel(&mut self, p: Point, color: Rgb565) {
+        assert!(
+            self.bounding_box().contains(p),
+            "pixel {p} is out of bounds"
+        );
+        self.pixels[p.y as usize * Self::SIZE.width as usize + p.x as usize] = Some(color);
+    }
+}
+
+impl DrawTarget for RawDisplay {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            self.set_pixel(p, color);
+        }
+
+        Ok(())
+    }
+}
+
+impl OriginDimensions for RawDisplay {
+    fn size(&self) -> Size {
+        Self::SIZE
+    }
+}
+
+impl RawDrawTarget for RawDisplay {
+    const RAW_FORMAT: RawFormat = RawFormat::Rgb565Le;
+
+    fn fill_contiguous_raw(&mut self, area: &Rectangle, data: &[u8]) -> Result<(), Self::Error> {
+        assert_eq!(
+            data.len(),
+            area.size.width as usize * area.size.height as usize * 2
+        );
+
+        for (p, bytes) in area.points().zip(data.chunks_exact(2)) {
+            let raw = RawU16::new(u16::from_le_bytes([bytes[0], bytes[1]]));
+            self.set_pixel(p, raw.into());
+        }
+        self.raw_transfers.push(*area);
+
+        Ok(())
+    }
+}
+
+/// Draws an image by using `draw_raw` and checks that the result matches `Bmp::draw`.
+fn draw_raw(data: &[u8]) -> RawDisplay {
+    let bmp = Bmp::<Rgb565>::from_slice(data).unwrap();
+    let position = Point::new(3, 5);
+
+    let mut display = RawDisplay::new();
+    bmp.draw_raw(&mut display, position).unwrap();
+
+    let mut expected = RawDisplay::new();
+    bmp.draw(&mut expected.translated(position)).unwrap();
+    assert_eq!(display.pixels, expected.pixels);
+
+    display
+}
+
+/// Converts a bottom-up image into a top-down image.
+fn to_top_down(data: &[u8]) -> Vec<u8> {
+    let image_data_start = u32::from_le_bytes(data[10..14].try_into().unwrap()) as usize;
+    let width = i32::from_le_bytes(data[18..22].try_into().unwrap()) as usize;
+    let height = i32::from_le_bytes(data[22..26].try_into().unwrap());
+    let bpp = u16::from_le_bytes(data[28..30].try_into().unwrap()) as usize;
+    let bytes_per_row = (width * bpp).div_ceil(32) * 4;
+
+    let mut output = data[..image_data_start].to_vec();
+    output[22..26].copy_from_slice(&(-height).to_le_bytes());
+
+    let rows = data[image_data_start..]
+        .chunks_exact(bytes_per_row)
+        .take(height as usize);
+    for row in rows.rev() {
+        output.extend_from_slice(row);
+    }
+
+    output
+}
+
+#[test]
+fn top_down_without_padding() {
+    let data = to_top_down(include_bytes!("./logo-rgb565.bmp"));
+    let display = draw_raw(&data);
+
+    // The image data is transferred in a single call.
+    assert_eq!(
+        display.raw_transfers,
+        [Rectangle::new(Point::new(3, 5), Size::new(240, 320))]
+    );
+}
+
+#[test]
+fn image_size_field() {
+    let data = to_top_down(include_bytes!("./logo-rgb565.bmp"));
+
+    // The image size field of this BI_BITFIELDS image includes additional bytes.
+    let mut padded = data.clone();
+    padded[34..38].copy_from_slice(&(240 * 320 * 2 + 4u32).to_le_bytes());
+    padded.extend_from_slice(&[0xFF; 4]);
+    let display = draw_raw(&padded);
+    assert_eq!(
+        display.raw_transfers,
+        [Rectangle::new(Point::new(3, 5), Size::new(240, 320))]
+    );
+
+    // The image size field is set to zero.
+    let mut zero = data;
+    zero[34..38].copy_from_slice(&0u32.to_le_bytes());
+    let display = draw_raw(&zero);
+    assert_eq!(
+        display.raw_transfers,
+        [Rectangle::new(Point::new(3, 5), Size::new(240, 320))]
+    );
+}
+
+#[test]
+fn bottom_up() {
+    let display = draw_raw(include_bytes!("./logo-rgb565.bmp"));
+
+    // The rows are transferred individually in top-down order.
+    assert_eq!(display.raw_transfers.len(), 320);
+    for (y, area) in (5..).zip(&display.raw_transfers) {
+        assert_eq!(*area, Rectangle::new(Point::new(3, y), Size::new(240, 1)));
+    }
+}
+
+#[test]
+fn padding() {
+    // 9 pixel wide rows are padded to 20 bytes.
+    let data = to_top_down(include_bytes!("./issue_136.bmp"));
+    let display = draw_raw(&data);
+
+    assert_eq!(display.raw_transfers.len(), 5);
+}
+
+#[test]
+fn format_mismatch() {
+    for data in [
+        include_bytes!("./logo-rgb555.bmp").as_slice(),
+        include_bytes!("./logo-rgb888-24bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp-rle8.bmp").as_slice(),
+    ] {
+        let display = draw_raw(data);
+        assert_eq!(display.raw_transfers, []);
+    }
+}
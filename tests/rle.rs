@@ -0,0 +1,242 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::{Bmp, RawBmp, RawPixel};
+
+const RLE8_DELTA: &[u8] = include_bytes!("./rle8_delta.bmp");
+const RLE4_DELTA: &[u8] = include_bytes!("./rle4_delta.bmp");
+
+const EXPECTED_PATTERN: &[&str] = &[
+    "WKW   ", //
+    "    BB", //
+    "GG    ", //
+    "RRRRRR", //
+];
+
+#[test]
+fn draw_delta() {
+    for data in [RLE8_DELTA, RLE4_DELTA] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+        let mut display = MockDisplay::new();
+        Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+
+        display.assert_pattern(EXPECTED_PATTERN);
+    }
+}
+
+#[test]
+fn pixels_delta() {
+    for data in [RLE8_DELTA, RLE4_DELTA] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+        let mut display = MockDisplay::new();
+        display.draw_iter(bmp.pixels()).unwrap();
+
+        display.assert_pattern(EXPECTED_PATTERN);
+    }
+}
+
+#[test]
+fn raw_pixels_delta() {
+    for data in [RLE8_DELTA, RLE4_DELTA] {
+        let bmp = RawBmp::from_slice(data).unwrap();
+
+        let pixels: Vec<RawPixel> = bmp.pixels().skip(6).collect();
+        let expected = [
+            (0, 2, 2),
+            (1, 2, 2),
+            (4, 1, 3),
+            (5, 1, 3),
+            (0, 0, 4),
+            (1, 0, 0),
+        ];
+        let expected = expected.map(|(x, y, color)| RawPixel {
+            position: Point::new(x, y),
+            color,
+        });
+
+        assert_eq!(&pixels[..expected.len()], &expected);
+        assert_eq!(pixels.len(), expected.len() + 1);
+    }
+}
+
+#[test]
+fn raw_colors_delta() {
+    for data in [RLE8_DELTA, RLE4_DELTA] {
+        let bmp = RawBmp::from_slice(data).unwrap();
+
+        let colors: Vec<u32> = bmp.colors().collect();
+        assert_eq!(
+            colors,
+            [
+                1, 1, 1, 1, 1, 1, //
+                2, 2, 0, 0, 0, 0, //
+                0, 0, 0, 0, 3, 3, //
+                4, 0, 4, 0, 0, 0, //
+            ]
+        );
+    }
+}
+
+#[test]
+fn pixel_delta() {
+    for data in [RLE8_DELTA, RLE4_DELTA] {
+        let bmp = RawBmp::from_slice(data).unwrap();
+
+        assert_eq!(bmp.pixel(Point::new(0, 0)), Some(4));
+        assert_eq!(bmp.pixel(Point::new(3, 0)), None);
+        assert_eq!(bmp.pixel(Point::new(1, 0)), Some(0));
+        assert_eq!(bmp.pixel(Point::new(5, 1)), Some(3));
+        assert_eq!(bmp.pixel(Point::new(2, 2)), None);
+        assert_eq!(bmp.pixel(Point::new(5, 3)), Some(1));
+        assert_eq!(bmp.pixel(Point::new(6, 3)), None);
+        assert_eq!(bmp.pixel(Point::new(0, 4)), None);
+
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        assert_eq!(bmp.pixel(Point::new(0, 2)), Some(Rgb888::GREEN));
+        assert_eq!(bmp.pixel(Point::new(3, 0)), None);
+    }
+}
+
+/// Creates a 2x3 pixel RLE compressed image.
+///
+/// Index 5 in the color table is red and index 7 is green.
+fn rle_image(bpp: u16, compression_method: u32, image_data: &[u8]) -> Vec<u8> {
+    let image_data_start = 14 + 40 + 16 * 4;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(image_data_start + image_data.len() as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&image_data_start.to_le_bytes());
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&bpp.to_le_bytes());
+    data.extend_from_slice(&compression_method.to_le_bytes());
+    data.extend_from_slice(&(image_data.len() as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&16u32.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    for index in 0..16 {
+        let color: [u8; 4] = match index {
+            5 => [0, 0, 0xFF, 0],
+            7 => [0, 0xFF, 0, 0],
+            _ => [0; 4],
+        };
+        data.extend_from_slice(&color);
+    }
+    data.extend_from_slice(image_data);
+
+    data
+}
+
+#[test]
+fn consecutive_end_of_line() {
+    // The second end of line marker skips the middle row.
+    for data in [
+        rle_image(8, 1, &[2, 5, 0, 0, 0, 0, 2, 7, 0, 1]),
+        rle_image(4, 2, &[2, 0x55, 0, 0, 0, 0, 2, 0x77, 0, 1]),
+    ] {
+        let expected_pattern = [
+            "GG", //
+            "  ", //
+            "RR", //
+        ];
+
+        let bmp = Bmp::<Rgb888>::from_slice(&data).unwrap();
+
+        let mut display = MockDisplay::new();
+        Image::new(&bmp, Point::zero()).draw(&mut display).unwrap();
+        display.assert_pattern(&expected_pattern);
+
+        let mut display = MockDisplay::new();
+        display.draw_iter(bmp.pixels()).unwrap();
+        display.assert_pattern(&expected_pattern);
+
+        assert_eq!(bmp.pixel(Point::new(0, 0)), Some(Rgb888::GREEN));
+        assert_eq!(bmp.pixel(Point::new(1, 1)), None);
+        assert_eq!(bmp.pixel(Point::new(1, 2)), Some(Rgb888::RED));
+
+        let raw_bmp = bmp.as_raw();
+        let pixels: Vec<RawPixel> = raw_bmp.pixels().collect();
+        let expected = [(0, 2, 5), (1, 2, 5), (0, 0, 7), (1, 0, 7)].map(|(x, y, color)| RawPixel {
+            position: Point::new(x, y),
+            color,
+        });
+        assert_eq!(pixels, expected);
+
+        let mut index = [0; 3];
+        assert!(raw_bmp.build_rle_index(&mut index));
+        assert_eq!(index, [0, 4, 6]);
+
+        let indexed_bmp = raw_bmp.with_rle_index(&index);
+        for p in Rectangle::new(Point::zero(), Size::new(2, 3)).points() {
+            assert_eq!(indexed_bmp.pixel(p), raw_bmp.pixel(p), "pixel at {p}");
+        }
+    }
+}
+
+#[test]
+fn rle_index() {
+    for data in [RLE8_DELTA, RLE4_DELTA] {
+        let bmp = RawBmp::from_slice(data).unwrap();
+
+        assert!(!bmp.build_rle_index(&mut [0; 3]));
+
+        let mut index = [0; 4];
+        assert!(bmp.build_rle_index(&mut index));
+        // The third row starts in the middle of a delta escape. The other rows start after the
+        // end of line markers of the previous rows.
+        assert_eq!(index, [0, 4, u32::MAX, 14]);
+
+        let indexed_bmp = bmp.with_rle_index(&index);
+        let size = bmp.header().image_size;
+        for p in Rectangle::new(Point::zero(), size).points() {
+            assert_eq!(indexed_bmp.pixel(p), bmp.pixel(p), "pixel at {p}");
+        }
+    }
+}
+
+#[test]
+fn rle_index_unsupported() {
+    let bmp = RawBmp::from_slice(include_bytes!("./logo-indexed-8bpp.bmp")).unwrap();
+
+    assert!(!bmp.build_rle_index(&mut [0; 320]));
+}
+
+#[test]
+fn pixel_logo() {
+    for (rle, uncompressed) in [
+        (
+            include_bytes!("./logo-indexed-8bpp-rle8.bmp").as_slice(),
+            include_bytes!("./logo-indexed-8bpp.bmp").as_slice(),
+        ),
+        (
+            include_bytes!("./logo-indexed-4bpp-rle4.bmp").as_slice(),
+            include_bytes!("./logo-indexed-4bpp.bmp").as_slice(),
+        ),
+    ] {
+        let rle = Bmp::<Rgb888>::from_slice(rle).unwrap();
+        let uncompressed = Bmp::<Rgb888>::from_slice(uncompressed).unwrap();
+
+        // Lookups without an index decode the image from the start.
+        for p in [Point::new(0, 0), Point::new(120, 160), Point::new(239, 319)] {
+            assert_eq!(rle.pixel(p), uncompressed.pixel(p), "pixel at {p}");
+        }
+
+        let mut index = vec![0; rle.size().height as usize];
+        assert!(rle.as_raw().build_rle_index(&mut index));
+        let rle = rle.with_rle_index(&index);
+
+        for p in rle.bounding_box().points() {
+            assert_eq!(rle.pixel(p), uncompressed.pixel(p), "pixel at {p}");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use embedded_graphics::{
+    image::GetPixel,
+    pixelcolor::{Rgb565, Rgb888},
+    prelude::*,
+};
+use tinybmp::{Bmp, RawBmp};
+
+#[test]
+fn rows_top_down_and_bottom_up() {
+    let bottom_up = RawBmp::from_slice(include_bytes!("./issue_8-image_bottom_up.bmp")).unwrap();
+    let top_down = RawBmp::from_slice(include_bytes!("./issue_8-image_top_down.bmp")).unwrap();
+
+    let size = bottom_up.header().image_size;
+    assert_eq!(bottom_up.rows().len(), size.height as usize);
+    assert!(bottom_up.rows().eq(top_down.rows()));
+
+    for row in bottom_up.rows() {
+        let bytes_per_pixel = usize::from(bottom_up.header().bpp.bits() / 8);
+        assert_eq!(row.data.len(), size.width as usize * bytes_per_pixel);
+
+        let x = size.width as i32 - 1;
+        let p = Point::new(x, row.index as i32);
+        let offset = x as usize * bytes_per_pixel;
+        let mut raw = [0; 4];
+        raw[..bytes_per_pixel].copy_from_slice(&row.data[offset..offset + bytes_per_pixel]);
+        assert_eq!(Some(u32::from_le_bytes(raw)), bottom_up.pixel(p));
+    }
+}
+
+#[test]
+fn rows_without_padding() {
+    // 8 pixels with 1 bit per pixel are stored in a single byte, followed by 3 padding bytes.
+    let bmp = RawBmp::from_slice(include_bytes!("./chessboard-8px-1bit.bmp")).unwrap();
+
+    let rows = bmp.rows().map(|row| row.data).collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            [0xCC],
+            [0xCC],
+            [0x33],
+            [0x33],
+            [0xCC],
+            [0xCC],
+            [0x33],
+            [0x33]
+        ]
+    );
+}
+
+#[test]
+fn rows_compressed() {
+    for data in [
+        include_bytes!("./logo-indexed-8bpp-rle8.bmp").as_slice(),
+        include_bytes!("./png_rgb.bmp").as_slice(),
+    ] {
+        let bmp = RawBmp::from_slice(data).unwrap();
+        assert_eq!(bmp.rows().next(), None);
+    }
+}
+
+#[test]
+fn read_row() {
+    for data in [
+        include_bytes!("./logo-indexed-4bpp.bmp").as_slice(),
+        include_bytes!("./logo-rgb565.bmp").as_slice(),
+        include_bytes!("./rle4_delta.bmp").as_slice(),
+        include_bytes!("./colors_rgb888_24bit.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb565>::from_slice(data).unwrap();
+        let size = bmp.size();
+
+        let mut row = vec![Rgb565::CSS_PINK; size.width as usize];
+        for y in 0..size.height {
+            row.fill(Rgb565::CSS_PINK);
+            assert_eq!(bmp.read_row(y, &mut row), size.width as usize);
+
+            for (x, color) in row.iter().enumerate() {
+                let expected = bmp
+                    .pixel(Point::new(x as i32, y as i32))
+                    .unwrap_or(Rgb565::CSS_PINK);
+                assert_eq!(*color, expected, "pixel at ({x}, {y})");
+            }
+        }
+    }
+}
+
+#[test]
+fn read_row_partial() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+
+    let mut row = [Rgb888::BLACK; 2];
+    assert_eq!(bmp.read_row(1, &mut row), 2);
+    assert_eq!(row, [Rgb888::BLUE, Rgb888::MAGENTA]);
+
+    let mut row = [Rgb888::BLACK; 8];
+    assert_eq!(bmp.read_row(0, &mut row), 4);
+    assert_eq!(
+        row[..5],
+        [
+            Rgb888::BLACK,
+            Rgb888::RED,
+            Rgb888::GREEN,
+            Rgb888::YELLOW,
+            Rgb888::BLACK
+        ]
+    );
+
+    assert_eq!(bmp.read_row(2, &mut row), 0);
+}
@@ -0,0 +1,269 @@
+#![cfg(feature = "embedded-io")]
+
+use core::convert::Infallible;
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use embedded_io::{ErrorType, Read, Seek, SeekFrom};
+use tinybmp::{Bmp, ParseError, StreamError, StreamingBmp};
+
+/// Reader which returns at most `chunk_len` bytes per read.
+struct ChunkReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    chunk_len: usize,
+}
+
+impl<'a> ChunkReader<'a> {
+    fn new(data: &'a [u8], chunk_len: usize) -> Self {
+        Self {
+            data,
+            position: 0,
+            chunk_len,
+        }
+    }
+}
+
+impl ErrorType for ChunkReader<'_> {
+    type Error = Infallible;
+}
+
+impl Read for ChunkReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let remaining = &self.data[self.position..];
+        let len = buf.len().min(remaining.len()).min(self.chunk_len);
+
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+impl Seek for ChunkReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        match pos {
+            SeekFrom::Start(offset) => self.position = offset as usize,
+            _ => unimplemented!(),
+        }
+
+        Ok(self.position as u64)
+    }
+}
+
+/// Draw target which checks that each pixel is drawn at most once.
+#[derive(Debug, PartialEq)]
+struct Framebuffer {
+    size: Size,
+    pixels: Vec<Option<Rgb888>>,
+}
+
+impl Framebuffer {
+    fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![None; size.width as usize * size.height as usize],
+        }
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            assert!(
+                self.bounding_box().contains(p),
+                "pixel {p} is out of bounds"
+            );
+
+            let pixel = &mut self.pixels[p.y as usize * self.size.width as usize + p.x as usize];
+            assert_eq!(*pixel, None, "pixel {p} was drawn twice");
+            *pixel = Some(color);
+        }
+
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+/// Checks that streaming an image draws the same pixels as `Bmp`.
+fn assert_stream(data: &[u8], row_buffer_len: usize) {
+    let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+    let mut reader = ChunkReader::new(data, 7);
+    let streaming_bmp = StreamingBmp::<Rgb888>::from_reader(&mut reader).unwrap();
+    assert_eq!(streaming_bmp.header(), bmp.as_raw().header());
+    assert_eq!(
+        streaming_bmp.color_table().as_ref(),
+        bmp.as_raw().color_table()
+    );
+    assert_eq!(reader.position, bmp.as_raw().header().image_data_start);
+
+    let mut row_buffer = vec![0; row_buffer_len];
+    let mut display = Framebuffer::new(bmp.size());
+    streaming_bmp
+        .draw(&mut reader, &mut display, &mut row_buffer)
+        .unwrap();
+
+    let mut expected = Framebuffer::new(bmp.size());
+    bmp.draw(&mut expected).unwrap();
+    assert_eq!(display, expected);
+}
+
+#[test]
+fn uncompressed() {
+    for data in [
+        include_bytes!("./logo-indexed-1bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-4bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp.bmp").as_slice(),
+        include_bytes!("./logo-rgb565.bmp").as_slice(),
+        include_bytes!("./logo-rgb888-24bpp.bmp").as_slice(),
+        include_bytes!("./colors_rgb101010.bmp").as_slice(),
+        include_bytes!("./colors_rgb64.bmp").as_slice(),
+        include_bytes!("./os2_core_8bpp.bmp").as_slice(),
+    ] {
+        let bmp = StreamingBmp::<Rgb888>::from_reader(&mut &data[..]).unwrap();
+
+        // Draw single rows and multiple rows at once.
+        assert_stream(data, bmp.row_buffer_len());
+        assert_stream(data, bmp.row_buffer_len() * 5 + 3);
+    }
+}
+
+#[test]
+fn row_order() {
+    assert_stream(include_bytes!("./issue_8-image_bottom_up.bmp"), 64);
+    assert_stream(include_bytes!("./issue_8-image_top_down.bmp"), 64);
+}
+
+#[test]
+fn rle() {
+    for data in [
+        include_bytes!("./logo-indexed-4bpp-rle4.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp-rle8.bmp").as_slice(),
+        include_bytes!("./rle4_delta.bmp").as_slice(),
+        include_bytes!("./rle8_delta.bmp").as_slice(),
+        include_bytes!("./pr_50_rle4_padding.bmp").as_slice(),
+    ] {
+        let bmp = StreamingBmp::<Rgb888>::from_reader(&mut &data[..]).unwrap();
+
+        assert_stream(data, bmp.row_buffer_len());
+        assert_stream(data, 4096);
+    }
+}
+
+#[test]
+fn draw_twice() {
+    let data = include_bytes!("./logo-indexed-8bpp-rle8.bmp");
+
+    let mut reader = ChunkReader::new(data, usize::MAX);
+    let bmp = StreamingBmp::<Rgb888>::from_reader(&mut reader).unwrap();
+    let mut row_buffer = [0; 1024];
+
+    let mut display = Framebuffer::new(bmp.size());
+    bmp.draw(&mut reader, &mut display, &mut row_buffer)
+        .unwrap();
+    assert_eq!(reader.position, data.len());
+
+    bmp.seek_to_image_data(&mut reader).unwrap();
+    let mut display2 = Framebuffer::new(bmp.size());
+    bmp.draw(&mut reader, &mut display2, &mut row_buffer)
+        .unwrap();
+    assert_eq!(display2, display);
+}
+
+#[test]
+fn buffer_too_small() {
+    for data in [
+        include_bytes!("./logo-rgb565.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp-rle8.bmp").as_slice(),
+    ] {
+        let mut reader = data;
+        let bmp = StreamingBmp::<Rgb888>::from_reader(&mut reader).unwrap();
+
+        let mut row_buffer = [0; 16];
+        assert_eq!(
+            bmp.draw(
+                &mut reader,
+                &mut Framebuffer::new(bmp.size()),
+                &mut row_buffer
+            ),
+            Err(StreamError::BufferTooSmall)
+        );
+    }
+}
+
+#[test]
+fn unexpected_end_of_file() {
+    let data = include_bytes!("./logo-indexed-8bpp.bmp");
+
+    assert_eq!(
+        StreamingBmp::<Rgb888>::from_reader(&mut &data[..100]),
+        Err(StreamError::Parse(ParseError::UnexpectedEndOfFile))
+    );
+
+    let mut reader = &data[..data.len() - 1];
+    let bmp = StreamingBmp::<Rgb888>::from_reader(&mut reader).unwrap();
+    let mut display = Framebuffer::new(bmp.size());
+    assert_eq!(
+        bmp.draw(&mut reader, &mut display, &mut [0; 1024]),
+        Err(StreamError::Parse(ParseError::UnexpectedEndOfFile))
+    );
+}
+
+#[test]
+fn large_color_table() {
+    // 2x1 pixel image with 8 BPP and a color table with 270 entries.
+    let colors_used = 270u32;
+    let image_data_start = 14 + 40 + colors_used * 4;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(image_data_start + 4).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&image_data_start.to_le_bytes());
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&8u16.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&4u32.to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&colors_used.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    for index in 0..colors_used {
+        data.extend_from_slice(&[index as u8, 0, 0xFF, 0]);
+    }
+    data.extend_from_slice(&[1, 255, 0, 0]);
+
+    let mut reader = data.as_slice();
+    let bmp = StreamingBmp::<Rgb888>::from_reader(&mut reader).unwrap();
+    assert_eq!(bmp.color_table().unwrap().len(), 256);
+
+    let mut display = Framebuffer::new(bmp.size());
+    bmp.draw(&mut reader, &mut display, &mut [0; 4]).unwrap();
+
+    let mut expected = Framebuffer::new(bmp.size());
+    Bmp::<Rgb888>::from_slice(&data)
+        .unwrap()
+        .draw(&mut expected)
+        .unwrap();
+    assert_eq!(display, expected);
+    assert_eq!(
+        display.pixels,
+        [
+            Some(Rgb888::new(0xFF, 0, 1)),
+            Some(Rgb888::new(0xFF, 0, 255))
+        ]
+    );
+}
@@ -0,0 +1,130 @@
+use embedded_graphics::{
+    image::{GetPixel, Image},
+    mock_display::MockDisplay,
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+};
+use tinybmp::Bmp;
+
+/// Checks that the pixels drawn by `draw_sub_image` match the pixels returned by `GetPixel`.
+fn assert_sub_image(bmp: &Bmp<Rgb888>, area: Rectangle) {
+    assert_sub_image_eq(bmp, bmp, area);
+}
+
+/// Checks that the pixels drawn by `draw_sub_image` match the pixels in a reference image.
+fn assert_sub_image_eq(bmp: &Bmp<Rgb888>, reference: &Bmp<Rgb888>, area: Rectangle) {
+    let mut display = MockDisplay::new();
+    bmp.draw_sub_image(&mut display, &area).unwrap();
+
+    for p in Rectangle::new(Point::zero(), area.size).points() {
+        assert_eq!(
+            display.get_pixel(p),
+            reference.pixel(area.top_left + p),
+            "pixel at {p} in area {area:?}"
+        );
+    }
+}
+
+#[test]
+fn uncompressed() {
+    for data in [
+        include_bytes!("./logo-indexed-1bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-4bpp.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp.bmp").as_slice(),
+        include_bytes!("./logo-rgb555.bmp").as_slice(),
+        include_bytes!("./logo-rgb565.bmp").as_slice(),
+        include_bytes!("./logo-rgb888-24bpp.bmp").as_slice(),
+        include_bytes!("./logo-rgb888-32bpp.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+        for area in [
+            Rectangle::new(Point::new(0, 0), Size::new(64, 64)),
+            Rectangle::new(Point::new(37, 101), Size::new(50, 3)),
+            Rectangle::new(Point::new(200, 280), Size::new(40, 40)),
+            Rectangle::new(Point::new(13, 0), Size::new(1, 64)),
+        ] {
+            assert_sub_image(&bmp, area);
+        }
+    }
+}
+
+#[test]
+fn small_images() {
+    for data in [
+        include_bytes!("./colors_gray2_2bpp.bmp").as_slice(),
+        include_bytes!("./colors_rgb64.bmp").as_slice(),
+        include_bytes!("./colors_rgb101010.bmp").as_slice(),
+        include_bytes!("./issue_8-image_top_down.bmp").as_slice(),
+        include_bytes!("./issue_8-image_bottom_up.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+
+        for area in [
+            Rectangle::new(Point::new(1, 1), Size::new(1, 1)),
+            Rectangle::new(Point::new(1, 0), Size::new(2, 2)),
+            Rectangle::new(Point::new(0, 1), Size::new(4, 1)),
+        ] {
+            assert_sub_image(&bmp, area);
+        }
+    }
+}
+
+#[test]
+fn rle() {
+    for data in [
+        include_bytes!("./logo-indexed-4bpp-rle4.bmp").as_slice(),
+        include_bytes!("./logo-indexed-8bpp-rle8.bmp").as_slice(),
+        include_bytes!("./pr_50_rle4_padding.bmp").as_slice(),
+        include_bytes!("./rle4_delta.bmp").as_slice(),
+        include_bytes!("./rle8_delta.bmp").as_slice(),
+    ] {
+        let bmp = Bmp::<Rgb888>::from_slice(data).unwrap();
+        let mut index = [0; 320];
+        bmp.as_raw().build_rle_index(&mut index);
+        // The row index speeds up the pixel lookups in the reference image.
+        let indexed_bmp = bmp.with_rle_index(&index);
+
+        for area in [
+            Rectangle::new(Point::new(0, 0), Size::new(64, 64)),
+            Rectangle::new(Point::new(3, 1), Size::new(2, 2)),
+            Rectangle::new(Point::new(37, 101), Size::new(50, 3)),
+            Rectangle::new(Point::new(200, 280), Size::new(40, 40)),
+        ] {
+            assert_sub_image_eq(&bmp, &indexed_bmp, area);
+            assert_sub_image(&indexed_bmp, area);
+        }
+    }
+}
+
+#[test]
+fn partially_outside() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./colors_rgb888_24bit.bmp")).unwrap();
+
+    let mut display = MockDisplay::new();
+    bmp.draw_sub_image(
+        &mut display,
+        &Rectangle::new(Point::new(-1, 1), Size::new(4, 2)),
+    )
+    .unwrap();
+
+    display.assert_pattern(&[
+        " BMC", //
+    ]);
+}
+
+#[test]
+fn outside() {
+    let bmp = Bmp::<Rgb888>::from_slice(include_bytes!("./rle8_delta.bmp")).unwrap();
+
+    let mut display = MockDisplay::<Rgb888>::new();
+    Image::new(
+        &bmp.sub_image(&Rectangle::new(Point::new(6, 0), Size::new(2, 2))),
+        Point::zero(),
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    display.assert_pattern(&[]);
+}
@@ -0,0 +1,98 @@
+use embedded_graphics::{
+    image::Image, mock_display::MockDisplay, pixelcolor::Rgb888, prelude::*, primitives::Rectangle,
+};
+use tinybmp::{Bmp, Tileset};
+
+const CHESSBOARD: &[u8] = include_bytes!("./chessboard-8px-24bit.bmp");
+
+fn draw<T>(image: &T) -> MockDisplay<Rgb888>
+where
+    T: ImageDrawable<Color = Rgb888>,
+{
+    let mut display = MockDisplay::new();
+    Image::new(image, Point::zero()).draw(&mut display).unwrap();
+    display
+}
+
+#[test]
+fn tiles() {
+    let bmp = Bmp::<Rgb888>::from_slice(CHESSBOARD).unwrap();
+    let tileset = Tileset::new(bmp, Size::new(2, 2));
+
+    assert_eq!(tileset.columns(), 4);
+    assert_eq!(tileset.rows(), 4);
+    assert_eq!(tileset.len(), 16);
+
+    draw(&tileset.tile(0).unwrap()).assert_pattern(&[
+        "WW", //
+        "WW", //
+    ]);
+    draw(&tileset.tile(5).unwrap()).assert_pattern(&[
+        "WW", //
+        "WW", //
+    ]);
+    draw(&tileset.tile(6).unwrap()).assert_pattern(&[
+        "KK", //
+        "KK", //
+    ]);
+    assert!(tileset.tile(16).is_none());
+}
+
+#[test]
+fn spacing_and_margin() {
+    let bmp = Bmp::<Rgb888>::from_slice(CHESSBOARD).unwrap();
+    let tileset = Tileset::new(bmp, Size::new(2, 2))
+        .with_margin(1)
+        .with_spacing(2);
+
+    assert_eq!(tileset.columns(), 2);
+    assert_eq!(tileset.rows(), 2);
+    assert_eq!(
+        tileset.tile_area(3),
+        Some(Rectangle::new(Point::new(5, 5), Size::new(2, 2)))
+    );
+
+    draw(&tileset.tile(3).unwrap()).assert_pattern(&[
+        "WK", //
+        "KW", //
+    ]);
+}
+
+#[test]
+fn incomplete_tiles() {
+    let bmp = Bmp::<Rgb888>::from_slice(CHESSBOARD).unwrap();
+
+    let tileset = Tileset::new(bmp, Size::new(3, 5));
+    assert_eq!(tileset.columns(), 2);
+    assert_eq!(tileset.rows(), 1);
+
+    let tileset = Tileset::new(bmp, Size::new(9, 1));
+    assert!(tileset.is_empty());
+    assert!(tileset.tile(0).is_none());
+
+    let tileset = Tileset::new(bmp, Size::zero());
+    assert!(tileset.is_empty());
+}
+
+#[test]
+fn regions() {
+    const REGIONS: &[(&str, Rectangle)] = &[
+        ("top", Rectangle::new(Point::new(0, 0), Size::new(8, 1))),
+        ("corner", Rectangle::new(Point::new(6, 6), Size::new(4, 4))),
+    ];
+
+    let bmp = Bmp::<Rgb888>::from_slice(CHESSBOARD).unwrap();
+    let tileset = Tileset::new(bmp, Size::new(2, 2)).with_regions(REGIONS);
+
+    draw(&tileset.region("top").unwrap()).assert_pattern(&[
+        "WWKKWWKK", //
+    ]);
+
+    // Regions are clipped to the image.
+    draw(&tileset.region("corner").unwrap()).assert_pattern(&[
+        "WW", //
+        "WW", //
+    ]);
+
+    assert!(tileset.region("missing").is_none());
+}